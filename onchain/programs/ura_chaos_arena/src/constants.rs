@@ -0,0 +1,60 @@
+pub const SECONDS_PER_DAY: i64 = 86_400;
+pub const MAX_DAYS_AHEAD: u8 = 31;
+// Joins may name the previous entry day for this long after it rolls over (clock skew between
+// building and landing a transaction)
+pub const JOIN_GRACE_MAX_SECS: u32 = 300;
+// Season passes come in these sizes; the discount is capped so passes can't be near-free
+pub const PASS_SIZES_DAYS: [u16; 2] = [7, 30];
+pub const PASS_DISCOUNT_BPS_MAX: u16 = 5_000;
+// Profile XP: every match played, plus a bonus per prize and another for 1st place.
+// The level curve itself is in math (level_for_xp).
+pub const XP_PER_MATCH: u64 = 100;
+pub const XP_PER_PRIZE: u64 = 250;
+pub const XP_PER_TOP1: u64 = 1_000;
+// Team arenas: at most this share of the prize pool funds the guild bucket, which the top
+// guilds split 50/30/20. The guild cap keeps commit_guild_results within one transaction.
+pub const GUILD_PRIZE_BPS_MAX: u16 = 5_000;
+pub const MAX_GUILDS_PER_MATCH: u32 = 20;
+pub const GUILD_MAX_MEMBERS: u32 = 50;
+// 1v1 challenges: bounds on the acceptance window and match length, and on the fee taken
+// from the pot (sent to the revenue wallet)
+pub const CHALLENGE_WINDOW_MAX_SECS: u32 = 7 * 86_400;
+pub const CHALLENGE_FEE_BPS_MAX: u16 = 1_000;
+// The referee's time to settle an ended challenge; after it either side can take their stake back
+pub const CHALLENGE_SETTLE_GRACE_SECS: i64 = 3 * 86_400;
+// Prediction pools not settled this long after their match day ends stop resolving and refund every bet
+pub const PREDICTION_SETTLE_WINDOW_SECS: i64 = 7 * 86_400;
+// URACHAOS staking: rewards per staked atom are tracked in units of 1/REWARD_SCALE lamports
+pub const REWARD_SCALE: u128 = 1_000_000_000_000;
+pub const UNSTAKE_COOLDOWN_MAX_SECS: u32 = 30 * 86_400;
+// Emergency pause bits in ArenaConfig::paused_flags, one per instruction group
+pub const PAUSE_JOIN: u8 = 1 << 0; // every ticket purchase and waitlist admission
+pub const PAUSE_FINALIZE: u8 = 1 << 1;
+pub const PAUSE_ALLOCATE: u8 = 1 << 2;
+pub const PAUSE_CLAIM: u8 = 1 << 3; // every payout, refund and withdrawal out of a program account
+pub const PAUSE_BURN: u8 = 1 << 4;
+pub const PAUSE_DEPOSIT: u8 = 1 << 5; // challenges, bets and URACHAOS stakes
+pub const PAUSE_ALL: u8 = PAUSE_JOIN | PAUSE_FINALIZE | PAUSE_ALLOCATE | PAUSE_CLAIM | PAUSE_BURN | PAUSE_DEPOSIT;
+// Every account ends with a layout version and reserved bytes, so new fields can take reserved
+// space; layouts that outgrow it are grown in place by the migrate instruction. The IDL parser
+// only accepts literal array lengths, so `reserved` fields spell out what is left of RESERVED_BYTES;
+// accounts with fields taken from it name that remainder in their RESERVED constant.
+pub const ACCOUNT_VERSION: u8 = 1;
+pub const RESERVED_BYTES: usize = 32;
+// Payout-critical settings change through the admin timelock; its delay is bounded
+pub const ADMIN_TIMELOCK_DEFAULT_SECS: u32 = 2 * 86_400;
+pub const ADMIN_TIMELOCK_MIN_SECS: u32 = 86_400;
+pub const ADMIN_TIMELOCK_MAX_SECS: u32 = 30 * 86_400;
+// Oracle thresholds: defaults applied at initialize_config, bounds enforced on update
+pub const PYTH_STALENESS_DEFAULT: u64 = 120;
+pub const PYTH_STALENESS_MIN: u64 = 5;
+pub const PYTH_STALENESS_MAX: u64 = 900;
+pub const PYTH_CONF_BPS_DEFAULT: u16 = 500;
+pub const PYTH_CONF_BPS_MIN: u16 = 10;
+pub const PYTH_CONF_BPS_MAX: u16 = 2_000;
+// Limits the math crate is tested up to: the ticket price cap in USD micro-units (keeps the
+// lamports conversion in range), the most minimum tickets a tier may weigh an entry at, and
+// the prediction fee cap
+pub use crate::math::{PREDICTION_FEE_BPS_MAX, STAKE_MULTIPLIER_MAX, TICKET_USD_MICROS_MAX};
+// Prize pool split between rank 1, group 2 and group 3, defined with the payout math
+pub use crate::math::{GROUP2_SHARE_PCT, GROUP3_SHARE_PCT, RANK1_SHARE_PCT};
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+use crate::math::MathError;
+
+#[error_code]
+pub enum ArenaError {
+    #[msg("invalid amount")] InvalidAmount,
+    #[msg("ticket amount below minimum")] TicketTooCheap,
+    #[msg("wrong match account for current day")] WrongMatchForDay,
+    #[msg("match already closed or not open")] MatchClosed,
+    #[msg("too early to finalize")] TooEarlyToFinalize,
+    #[msg("match already finalized")] MatchAlreadyFinalized,
+    #[msg("pot is empty")] EmptyPot,
+    #[msg("overflow")] Overflow,
+    #[msg("invalid rank")] InvalidRank,
+    #[msg("zero allocation")] ZeroAllocation,
+    #[msg("already claimed")] AlreadyClaimed,
+    #[msg("match not finalized")] MatchNotFinalized,
+    #[msg("pyth price error")] PythError,
+    #[msg("pyth price stale")] PythStale,
+    #[msg("pyth confidence too wide")] PythConfTooWide,
+    #[msg("allocation not owned by winner")] InvalidAllocationOwner,
+    #[msg("pyth price update not fully verified")] PythUnverified,
+    #[msg("pyth price update is for a different feed")] PythFeedMismatch,
+    #[msg("pyth price update account required")] MissingPriceUpdate,
+    #[msg("invalid oracle policy")] InvalidOraclePolicy,
+    #[msg("oracle threshold out of bounds")] InvalidOracleThreshold,
+    #[msg("ticket price out of bounds")] InvalidTicketPrice,
+    #[msg("no fixed ticket price set for emergency mode")] NoFixedTicketPrice,
+    #[msg("ticket price above caller's max_lamports")] TicketPriceAboveMax,
+    #[msg("stake multiplier out of bounds")] InvalidStakeMultiplier,
+    #[msg("not all winner allocations recorded yet")] AllocationsPending,
+    #[msg("player already joined this match")] AlreadyJoined,
+    #[msg("match is full")] MatchFull,
+    #[msg("match is not full; join directly")] MatchNotFull,
+    #[msg("waitlist disabled for this tier")] WaitlistDisabled,
+    #[msg("waitlist entries must be admitted in order")] WaitlistOutOfOrder,
+    #[msg("match still open")] MatchStillOpen,
+    #[msg("entries for this match are closed")] JoinClosed,
+    #[msg("invalid join cutoff or entry window")] InvalidEntryWindow,
+    #[msg("day is not within the tier's pre-sale horizon")] InvalidFutureDay,
+    #[msg("season passes are 7 or 30 days")] InvalidPassSize,
+    #[msg("pass discount out of bounds")] InvalidPassDiscount,
+    #[msg("season pass still has credits")] PassStillActive,
+    #[msg("no season pass credits left")] NoPassCredits,
+    #[msg("invalid achievement metric or threshold")] InvalidAchievementMetric,
+    #[msg("achievement is retired")] AchievementInactive,
+    #[msg("profile does not meet the achievement yet")] AchievementLocked,
+    #[msg("achievement badge already enabled")] BadgeAlreadyEnabled,
+    #[msg("achievement has no badge")] BadgeNotEnabled,
+    #[msg("badge already minted")] BadgeAlreadyMinted,
+    #[msg("referral share out of bounds")] InvalidReferralBps,
+    #[msg("referred player must pass their referrer's account")] MissingReferral,
+    #[msg("guild prize share out of bounds")] InvalidGuildPrizeBps,
+    #[msg("tier is not a team arena")] NotTeamArena,
+    #[msg("team arena entries need a guild membership and the guild's standing")] GuildRequired,
+    #[msg("too many guilds in this match")] GuildLimitReached,
+    #[msg("guild is closed; founder must co-sign")] GuildClosed,
+    #[msg("guild roster is full")] GuildFull,
+    #[msg("guild standings missing or mismatched")] GuildStandingsIncomplete,
+    #[msg("guild results already committed")] GuildResultsCommitted,
+    #[msg("guild results not committed yet")] GuildResultsPending,
+    #[msg("only the member or the guild founder can do this")] NotGuildMemberOrFounder,
+    #[msg("guild founder cannot leave")] FounderCannotLeave,
+    #[msg("challenge fee out of bounds")] InvalidChallengeFee,
+    #[msg("challenge window out of bounds")] InvalidChallengeWindow,
+    #[msg("not a valid opponent for this challenge")] InvalidOpponent,
+    #[msg("challenge is not open")] ChallengeNotOpen,
+    #[msg("challenge is not active")] ChallengeNotActive,
+    #[msg("challenge acceptance window has passed")] ChallengeExpired,
+    #[msg("challenge acceptance window still open")] ChallengeNotExpired,
+    #[msg("challenge still running")] ChallengeStillRunning,
+    #[msg("prediction fee out of bounds")] InvalidPredictionFee,
+    #[msg("invalid prediction kind")] InvalidPredictionKind,
+    #[msg("players in the match cannot bet on it")] PlayersCannotBet,
+    #[msg("prediction pool already settled")] PredictionSettled,
+    #[msg("prediction pool not settled yet")] PredictionPending,
+    #[msg("staking parameters out of bounds")] InvalidStakingParams,
+    #[msg("staking is enabled; pass the staking pool")] MissingStakingPool,
+    #[msg("unstake cooldown has not elapsed")] UnstakeCooldown,
+    #[msg("invalid token gate")] InvalidTokenGate,
+    #[msg("tier requires holding the gate token or a verified collection NFT")] TokenGateNotMet,
+    #[msg("invalid organizer bps")] InvalidOrganizerBps,
+    #[msg("wallet is not on the tournament allowlist")] NotInvited,
+    #[msg("tournament account required")] MissingTournament,
+    #[msg("invalid pause flags")] InvalidPauseFlags,
+    #[msg("this instruction is paused")] Paused,
+    #[msg("signer is neither the authority nor the guardian")] NotAuthorityOrGuardian,
+    #[msg("invalid admin action")] InvalidAdminAction,
+    #[msg("admin action is still timelocked")] TimelockNotElapsed,
+    #[msg("account cannot be migrated with this instruction")] InvalidMigration,
+    #[msg("account is already on the current layout")] AlreadyMigrated,
+    #[msg("invalid join grace window")] InvalidJoinGrace,
+    #[msg("freed seats go to the waitlist first")] WaitlistPending,
+    #[msg("pass the tier this admin action targets")] MissingTier,
+    #[msg("prediction pool missed its settlement deadline")] PredictionExpired,
+    #[msg("prediction pool can still be settled")] PredictionNotExpired,
+    #[msg("challenge can still be settled")] ChallengeNotAbandoned,
+    #[msg("only the creator or the opponent may do this")] NotChallengeParty,
+    #[msg("pyth feed id not set; see set_pyth_feed")] PythFeedUnset,
+    #[msg("wallets that bet on a match cannot join it")] BettorsCannotJoin,
+    #[msg("season pass has expired")] PassExpired,
+}
+
+// Math results are surfaced with `.map_err(ArenaError::from)?`
+impl From<MathError> for ArenaError {
+    fn from(e: MathError) -> Self {
+        match e {
+            MathError::Overflow => ArenaError::Overflow,
+            MathError::InvalidRank => ArenaError::InvalidRank,
+            MathError::InvalidAmount => ArenaError::InvalidAmount,
+            MathError::InvalidPrice => ArenaError::PythError,
+            MathError::ZeroAllocation => ArenaError::ZeroAllocation,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::state::AdminAction;
+
+#[event]
+pub struct AdminActionQueued {
+    pub id: u64,
+    pub action: AdminAction,
+    pub eta: i64,
+}
+
+#[event]
+pub struct AdminActionExecuted {
+    pub id: u64,
+    pub action: AdminAction,
+}
+
+#[event]
+pub struct AdminActionCancelled {
+    pub id: u64,
+}
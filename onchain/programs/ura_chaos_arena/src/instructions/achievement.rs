@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::{self, spl_token_2022, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+#[instruction(achievement_id: u16)]
+pub struct CreateAchievement<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"achievement", achievement_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Achievement::SIZE,
+    )]
+    pub achievement: Account<'info, Achievement>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAchievement<'info> {
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"achievement", achievement.achievement_id.to_le_bytes().as_ref()], bump = achievement.bump)]
+    pub achievement: Account<'info, Achievement>,
+}
+
+#[derive(Accounts)]
+pub struct EnableAchievementBadge<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"achievement", achievement.achievement_id.to_le_bytes().as_ref()], bump = achievement.bump)]
+    pub achievement: Account<'info, Achievement>,
+    /// CHECK: created here as a Token-2022 mint with the NonTransferable extension
+    #[account(mut, seeds = [b"badge", achievement.key().as_ref()], bump)]
+    pub badge_mint: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockAchievement<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [b"achievement", achievement.achievement_id.to_le_bytes().as_ref()], bump = achievement.bump)]
+    pub achievement: Account<'info, Achievement>,
+    #[account(seeds = [b"profile", profile.player.as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, PlayerProfile>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"unlock", achievement.key().as_ref(), profile.player.as_ref()],
+        bump,
+        space = 8 + AchievementUnlock::SIZE,
+    )]
+    pub unlock: Account<'info, AchievementUnlock>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintBadge<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: badge recipient, bound by the unlock record
+    pub player: UncheckedAccount<'info>,
+    #[account(seeds = [b"achievement", achievement.achievement_id.to_le_bytes().as_ref()], bump = achievement.bump)]
+    pub achievement: Account<'info, Achievement>,
+    #[account(
+        mut,
+        seeds = [b"unlock", achievement.key().as_ref(), player.key().as_ref()],
+        bump = unlock.bump,
+        has_one = player,
+    )]
+    pub unlock: Account<'info, AchievementUnlock>,
+    #[account(mut, address = achievement.badge_mint @ ArenaError::BadgeNotEnabled)]
+    pub badge_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = badge_mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program,
+    )]
+    pub badge_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn create_achievement(ctx: Context<CreateAchievement>, achievement_id: u16, metric: u8, threshold: u64) -> Result<()> {
+    AchievementMetric::from_u8(metric).ok_or(ArenaError::InvalidAchievementMetric)?;
+    require!(threshold > 0, ArenaError::InvalidAchievementMetric);
+    let a = &mut ctx.accounts.achievement;
+    a.achievement_id = achievement_id;
+    a.metric = metric;
+    a.threshold = threshold;
+    a.active = true;
+    a.badge_mint = Pubkey::default();
+    a.bump = ctx.bumps.achievement;
+    a.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn update_achievement(ctx: Context<UpdateAchievement>, threshold: u64, active: bool) -> Result<()> {
+    require!(threshold > 0, ArenaError::InvalidAchievementMetric);
+    let a = &mut ctx.accounts.achievement;
+    a.threshold = threshold;
+    a.active = active;
+    Ok(())
+}
+
+pub(crate) fn enable_achievement_badge(ctx: Context<EnableAchievementBadge>) -> Result<()> {
+    require!(ctx.accounts.achievement.badge_mint == Pubkey::default(), ArenaError::BadgeAlreadyEnabled);
+    let mint_key = ctx.accounts.badge_mint.key();
+    let achievement_key = ctx.accounts.achievement.key();
+    let badge_bump = ctx.bumps.badge_mint;
+    let badge_seeds: &[&[u8]] = &[b"badge", achievement_key.as_ref(), &[badge_bump]];
+
+    let space = spl_token_2022::extension::ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(
+        &[spl_token_2022::extension::ExtensionType::NonTransferable],
+    )?;
+    create_pda_account(
+        &ctx.accounts.authority,
+        &ctx.accounts.badge_mint.to_account_info(),
+        &ctx.accounts.system_program,
+        space,
+        &token_2022::ID,
+        badge_seeds,
+    )?;
+    let ix = spl_token_2022::instruction::initialize_non_transferable_mint(&token_2022::ID, &mint_key)?;
+    invoke_signed(
+        &ix,
+        &[ctx.accounts.badge_mint.to_account_info(), ctx.accounts.token_program.to_account_info()],
+        &[],
+    )?;
+    token_2022::initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_2022::InitializeMint2 { mint: ctx.accounts.badge_mint.to_account_info() },
+        ),
+        0,
+        &achievement_key,
+        None,
+    )?;
+
+    ctx.accounts.achievement.badge_mint = mint_key;
+    Ok(())
+}
+
+pub(crate) fn unlock_achievement(ctx: Context<UnlockAchievement>) -> Result<()> {
+    let a = &ctx.accounts.achievement;
+    require!(a.active, ArenaError::AchievementInactive);
+    let metric = AchievementMetric::from_u8(a.metric).ok_or(ArenaError::InvalidAchievementMetric)?;
+    let profile = &ctx.accounts.profile;
+    let value = profile_metric(profile, metric);
+    require!(value >= a.threshold, ArenaError::AchievementLocked);
+
+    let unlock = &mut ctx.accounts.unlock;
+    unlock.achievement = a.key();
+    unlock.player = profile.player;
+    unlock.value = value;
+    unlock.unlocked_at = Clock::get()?.unix_timestamp;
+    unlock.badge_minted = false;
+    unlock.bump = ctx.bumps.unlock;
+    unlock.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn mint_badge(ctx: Context<MintBadge>) -> Result<()> {
+    let unlock = &mut ctx.accounts.unlock;
+    require!(!unlock.badge_minted, ArenaError::BadgeAlreadyMinted);
+    unlock.badge_minted = true;
+
+    let a = &ctx.accounts.achievement;
+    let id_bytes = a.achievement_id.to_le_bytes();
+    let achievement_seeds: &[&[u8]] = &[b"achievement", &id_bytes, &[a.bump]];
+    token_2022::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_2022::MintTo {
+                mint: ctx.accounts.badge_mint.to_account_info(),
+                to: ctx.accounts.badge_account.to_account_info(),
+                authority: a.to_account_info(),
+            },
+            &[achievement_seeds],
+        ),
+        1,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,218 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::events::*;
+use crate::math::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct QueueAdminAction<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"admin_action", config.key().as_ref(), config.admin_action_count.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + PendingAdminAction::SIZE,
+    )]
+    pub pending: Account<'info, PendingAdminAction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        mut,
+        seeds = [b"admin_action", config.key().as_ref(), pending.id.to_le_bytes().as_ref()],
+        bump = pending.bump,
+        close = authority,
+    )]
+    pub pending: Account<'info, PendingAdminAction>,
+    // Required for SetStakingRevenueBps and SetStakingCooldown
+    #[account(mut, seeds = [b"staking", config.key().as_ref()], bump = staking_pool.bump)]
+    pub staking_pool: Option<Account<'info, StakingPool>>,
+    // Required for SetStakeWeighting, SetTeamMode and SetEmergencyPrice: the tier the action names
+    #[account(mut, seeds = [b"tier", [tier.tier_id].as_ref()], bump = tier.bump)]
+    pub tier: Option<Account<'info, ArenaTier>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminAction<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        mut,
+        seeds = [b"admin_action", config.key().as_ref(), pending.id.to_le_bytes().as_ref()],
+        bump = pending.bump,
+        close = authority,
+    )]
+    pub pending: Account<'info, PendingAdminAction>,
+}
+
+pub(crate) fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+    validate_admin_action(&action)?;
+    let now = Clock::get()?.unix_timestamp;
+    let config = &mut ctx.accounts.config;
+    let pending = &mut ctx.accounts.pending;
+    pending.id = config.admin_action_count;
+    pending.action = action.clone();
+    // Configs not yet migrated may still hold a 0 delay
+    let delay = config.admin_timelock_secs.max(ADMIN_TIMELOCK_MIN_SECS);
+    pending.eta = now.checked_add(delay as i64).ok_or(ArenaError::Overflow)?;
+    pending.bump = ctx.bumps.pending;
+    pending.version = ACCOUNT_VERSION;
+    config.admin_action_count += 1;
+    emit!(AdminActionQueued { id: pending.id, action, eta: pending.eta });
+    Ok(())
+}
+
+pub(crate) fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pending = &ctx.accounts.pending;
+    require!(now >= pending.eta, ArenaError::TimelockNotElapsed);
+    let tier = ctx.accounts.tier.as_mut().map(|t| (t.key(), &mut **t));
+    apply_admin_action(&mut ctx.accounts.config, ctx.accounts.staking_pool.as_deref_mut(), tier, &pending.action)?;
+    emit!(AdminActionExecuted { id: pending.id, action: pending.action.clone() });
+    Ok(())
+}
+
+pub(crate) fn cancel_admin_action(ctx: Context<CancelAdminAction>) -> Result<()> {
+    emit!(AdminActionCancelled { id: ctx.accounts.pending.id });
+    Ok(())
+}
+
+fn validate_admin_action(action: &AdminAction) -> Result<()> {
+    match *action {
+        AdminAction::SetAuthority(key) | AdminAction::SetRevenueWallet(key) => {
+            require!(key != Pubkey::default(), ArenaError::InvalidAdminAction);
+        }
+        AdminAction::SetReferralBps(bps) => require!(bps as u128 <= BPS_100, ArenaError::InvalidReferralBps),
+        AdminAction::SetChallengeFee(bps) => require!(bps <= CHALLENGE_FEE_BPS_MAX, ArenaError::InvalidChallengeFee),
+        AdminAction::SetPredictionFee(bps) => require!(bps <= PREDICTION_FEE_BPS_MAX, ArenaError::InvalidPredictionFee),
+        AdminAction::SetOrganizerBps(bps) => require!(bps as u128 <= BPS_100, ArenaError::InvalidOrganizerBps),
+        AdminAction::SetStakingRevenueBps(bps) => require!(bps as u128 <= BPS_100, ArenaError::InvalidStakingParams),
+        AdminAction::SetTimelockDelay(secs) => require!(
+            (ADMIN_TIMELOCK_MIN_SECS..=ADMIN_TIMELOCK_MAX_SECS).contains(&secs),
+            ArenaError::InvalidAdminAction
+        ),
+        AdminAction::SetStakeWeighting(tier, cap) => {
+            require!(tier != Pubkey::default(), ArenaError::InvalidAdminAction);
+            require!(cap <= STAKE_MULTIPLIER_MAX, ArenaError::InvalidStakeMultiplier);
+        }
+        AdminAction::SetTeamMode(tier, bps) => {
+            require!(tier != Pubkey::default(), ArenaError::InvalidAdminAction);
+            require!(bps <= GUILD_PRIZE_BPS_MAX, ArenaError::InvalidGuildPrizeBps);
+        }
+        // Pubkey::default() removes the guardian
+        AdminAction::SetGuardian(_) => {}
+        AdminAction::SetStakingCooldown(secs) => {
+            require!(secs <= UNSTAKE_COOLDOWN_MAX_SECS, ArenaError::InvalidStakingParams);
+        }
+        // 0 rejects joins while the oracle policy is EmergencyFixedPrice
+        AdminAction::SetEmergencyPrice(tier, _) => {
+            require!(tier != Pubkey::default(), ArenaError::InvalidAdminAction);
+        }
+    }
+    Ok(())
+}
+
+fn apply_admin_action(
+    config: &mut ArenaConfig,
+    staking_pool: Option<&mut StakingPool>,
+    tier: Option<(Pubkey, &mut ArenaTier)>,
+    action: &AdminAction,
+) -> Result<()> {
+    validate_admin_action(action)?;
+    match *action {
+        AdminAction::SetAuthority(key) => config.authority = key,
+        AdminAction::SetRevenueWallet(key) => config.revenue_wallet = key,
+        AdminAction::SetReferralBps(bps) => config.referral_bps = bps,
+        AdminAction::SetChallengeFee(bps) => config.challenge_fee_bps = bps,
+        AdminAction::SetPredictionFee(bps) => config.prediction_fee_bps = bps,
+        AdminAction::SetOrganizerBps(bps) => config.organizer_bps = bps,
+        AdminAction::SetStakingRevenueBps(bps) => {
+            staking_pool.ok_or(ArenaError::MissingStakingPool)?.revenue_bps = bps;
+        }
+        AdminAction::SetTimelockDelay(secs) => config.admin_timelock_secs = secs,
+        AdminAction::SetStakeWeighting(key, cap) => action_tier(tier, key)?.stake_weight_cap = cap,
+        AdminAction::SetTeamMode(key, bps) => action_tier(tier, key)?.guild_prize_bps = bps,
+        AdminAction::SetGuardian(key) => config.guardian = key,
+        AdminAction::SetStakingCooldown(secs) => {
+            staking_pool.ok_or(ArenaError::MissingStakingPool)?.cooldown_secs = secs;
+        }
+        AdminAction::SetEmergencyPrice(key, lamports) => action_tier(tier, key)?.fixed_lamports_per_ticket = lamports,
+    }
+    Ok(())
+}
+
+fn action_tier(tier: Option<(Pubkey, &mut ArenaTier)>, key: Pubkey) -> Result<&mut ArenaTier> {
+    let (passed, tier) = tier.ok_or(ArenaError::MissingTier)?;
+    require_keys_eq!(passed, key, ArenaError::MissingTier);
+    Ok(tier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admin_actions() {
+        let mut config = ArenaConfig::default();
+        let wallet = Pubkey::new_unique();
+        apply_admin_action(&mut config, None, None, &AdminAction::SetRevenueWallet(wallet)).unwrap();
+        apply_admin_action(&mut config, None, None, &AdminAction::SetReferralBps(2_500)).unwrap();
+        apply_admin_action(&mut config, None, None, &AdminAction::SetTimelockDelay(86_400)).unwrap();
+        assert_eq!((config.revenue_wallet, config.referral_bps, config.admin_timelock_secs), (wallet, 2_500, 86_400));
+
+        assert!(apply_admin_action(&mut config, None, None, &AdminAction::SetChallengeFee(CHALLENGE_FEE_BPS_MAX + 1)).is_err());
+        assert!(apply_admin_action(&mut config, None, None, &AdminAction::SetAuthority(Pubkey::default())).is_err());
+        assert!(apply_admin_action(&mut config, None, None, &AdminAction::SetStakingRevenueBps(100)).is_err());
+        let mut pool = StakingPool::default();
+        apply_admin_action(&mut config, Some(&mut pool), None, &AdminAction::SetStakingRevenueBps(100)).unwrap();
+        assert_eq!(pool.revenue_bps, 100);
+
+        // The delay can never drop below a day, so nothing executes in the slot it was queued
+        assert!(apply_admin_action(&mut config, None, None, &AdminAction::SetTimelockDelay(0)).is_err());
+        assert!(apply_admin_action(&mut config, None, None, &AdminAction::SetTimelockDelay(ADMIN_TIMELOCK_MIN_SECS - 1)).is_err());
+
+        // Tier actions only apply to the tier they name
+        let key = Pubkey::new_unique();
+        let mut tier = ArenaTier::default();
+        assert!(apply_admin_action(&mut config, None, None, &AdminAction::SetStakeWeighting(key, 5)).is_err());
+        let other = Some((Pubkey::new_unique(), &mut tier));
+        assert!(apply_admin_action(&mut config, None, other, &AdminAction::SetTeamMode(key, 1_000)).is_err());
+        apply_admin_action(&mut config, None, Some((key, &mut tier)), &AdminAction::SetStakeWeighting(key, 5)).unwrap();
+        apply_admin_action(&mut config, None, Some((key, &mut tier)), &AdminAction::SetTeamMode(key, 1_000)).unwrap();
+        assert_eq!((tier.stake_weight_cap, tier.guild_prize_bps), (5, 1_000));
+        let over = AdminAction::SetTeamMode(key, GUILD_PRIZE_BPS_MAX + 1);
+        assert!(apply_admin_action(&mut config, None, Some((key, &mut tier)), &over).is_err());
+
+        // Guardian rotation, the unstake cooldown and the emergency price wait out the delay too
+        let guardian = Pubkey::new_unique();
+        apply_admin_action(&mut config, None, None, &AdminAction::SetGuardian(guardian)).unwrap();
+        assert_eq!(config.guardian, guardian);
+        assert!(apply_admin_action(&mut config, None, None, &AdminAction::SetStakingCooldown(3_600)).is_err());
+        let too_long = AdminAction::SetStakingCooldown(UNSTAKE_COOLDOWN_MAX_SECS + 1);
+        assert!(apply_admin_action(&mut config, Some(&mut pool), None, &too_long).is_err());
+        apply_admin_action(&mut config, Some(&mut pool), None, &AdminAction::SetStakingCooldown(3_600)).unwrap();
+        assert_eq!(pool.cooldown_secs, 3_600);
+        assert!(apply_admin_action(&mut config, None, None, &AdminAction::SetEmergencyPrice(key, 5_000)).is_err());
+        apply_admin_action(&mut config, None, Some((key, &mut tier)), &AdminAction::SetEmergencyPrice(key, 5_000)).unwrap();
+        assert_eq!(tier.fixed_lamports_per_ticket, 5_000);
+
+        // The largest variant fits the reserved space
+        let action = AdminAction::SetEmergencyPrice(wallet, 1);
+        assert_eq!(action.try_to_vec().unwrap().len(), AdminAction::SIZE);
+    }
+}
@@ -0,0 +1,253 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+#[instruction(challenge_id: u64)]
+pub struct CreateChallenge<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"challenge", creator.key().as_ref(), &challenge_id.to_le_bytes()],
+        bump,
+        space = 8 + Challenge::SIZE,
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptChallenge<'info> {
+    #[account(mut)]
+    pub opponent: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        mut,
+        seeds = [b"challenge", challenge.creator.as_ref(), &challenge.challenge_id.to_le_bytes()],
+        bump = challenge.bump,
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleChallenge<'info> {
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        mut,
+        seeds = [b"challenge", creator.key().as_ref(), &challenge.challenge_id.to_le_bytes()],
+        bump = challenge.bump,
+        has_one = creator,
+        close = creator,
+    )]
+    pub challenge: Account<'info, Challenge>,
+    /// CHECK: challenge creator, receives the rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    /// CHECK: must be the creator or the opponent; checked in the handler
+    #[account(mut)]
+    pub winner: UncheckedAccount<'info>,
+    /// CHECK: revenue wallet can be any system account
+    #[account(mut, address = config.revenue_wallet)]
+    pub revenue_wallet: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelChallenge<'info> {
+    pub signer: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        mut,
+        seeds = [b"challenge", creator.key().as_ref(), &challenge.challenge_id.to_le_bytes()],
+        bump = challenge.bump,
+        has_one = creator,
+        close = creator,
+    )]
+    pub challenge: Account<'info, Challenge>,
+    /// CHECK: challenge creator, receives stake and rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundChallenge<'info> {
+    #[account(constraint = signer.key() == creator.key() || signer.key() == opponent.key() @ ArenaError::NotChallengeParty)]
+    pub signer: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        mut,
+        seeds = [b"challenge", creator.key().as_ref(), &challenge.challenge_id.to_le_bytes()],
+        bump = challenge.bump,
+        has_one = creator,
+        has_one = opponent,
+        close = creator,
+    )]
+    pub challenge: Account<'info, Challenge>,
+    /// CHECK: challenge creator, receives their stake and the rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+    /// CHECK: the accepted opponent, receives their stake
+    #[account(mut)]
+    pub opponent: UncheckedAccount<'info>,
+}
+
+fn check_new_challenge(
+    config: &ArenaConfig,
+    creator: &Pubkey,
+    opponent: &Pubkey,
+    stake: u64,
+    duration_secs: u32,
+    accept_within_secs: u32,
+) -> Result<()> {
+    require_not_paused(config, PAUSE_DEPOSIT)?;
+    require!(stake > 0 && stake >= config.min_ticket_lamports, ArenaError::TicketTooCheap);
+    require!(
+        duration_secs > 0 && duration_secs <= CHALLENGE_WINDOW_MAX_SECS
+            && accept_within_secs > 0 && accept_within_secs <= CHALLENGE_WINDOW_MAX_SECS,
+        ArenaError::InvalidChallengeWindow
+    );
+    require!(opponent != creator, ArenaError::InvalidOpponent);
+    Ok(())
+}
+
+fn check_accept(config: &ArenaConfig, c: &Challenge, opponent: &Pubkey, now: i64) -> Result<()> {
+    require_not_paused(config, PAUSE_DEPOSIT)?;
+    require!(c.status == ChallengeStatus::Open as u8, ArenaError::ChallengeNotOpen);
+    require!(now <= c.accept_deadline, ArenaError::ChallengeExpired);
+    require!(*opponent != c.creator, ArenaError::InvalidOpponent);
+    require!(c.opponent == Pubkey::default() || c.opponent == *opponent, ArenaError::InvalidOpponent);
+    Ok(())
+}
+
+pub(crate) fn create_challenge(
+    ctx: Context<CreateChallenge>,
+    challenge_id: u64,
+    opponent: Pubkey,
+    stake: u64,
+    duration_secs: u32,
+    accept_within_secs: u32,
+) -> Result<()> {
+    let creator = ctx.accounts.creator.key();
+    check_new_challenge(&ctx.accounts.config, &creator, &opponent, stake, duration_secs, accept_within_secs)?;
+
+    let c = &mut ctx.accounts.challenge;
+    c.creator = creator;
+    c.challenge_id = challenge_id;
+    c.opponent = opponent;
+    c.stake = stake;
+    c.duration_secs = duration_secs;
+    c.accept_deadline = Clock::get()?.unix_timestamp + accept_within_secs as i64;
+    c.status = ChallengeStatus::Open as u8;
+    c.bump = ctx.bumps.challenge;
+    c.version = ACCOUNT_VERSION;
+
+    let ix = system_instruction::transfer(&creator, &c.key(), stake);
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.creator.to_account_info(),
+            c.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        &[],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn accept_challenge(ctx: Context<AcceptChallenge>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let c = &mut ctx.accounts.challenge;
+    let opponent = ctx.accounts.opponent.key();
+    check_accept(&ctx.accounts.config, c, &opponent, now)?;
+
+    c.opponent = opponent;
+    c.ends_at = now + c.duration_secs as i64;
+    c.status = ChallengeStatus::Active as u8;
+
+    let ix = system_instruction::transfer(&opponent, &c.key(), c.stake);
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.opponent.to_account_info(),
+            c.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        &[],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn settle_challenge(ctx: Context<SettleChallenge>, winner: Pubkey) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let c = &ctx.accounts.challenge;
+    require!(c.status == ChallengeStatus::Active as u8, ArenaError::ChallengeNotActive);
+    require!(Clock::get()?.unix_timestamp >= c.ends_at, ArenaError::ChallengeStillRunning);
+    require!(winner == c.creator || winner == c.opponent, ArenaError::InvalidOpponent);
+    require_keys_eq!(ctx.accounts.winner.key(), winner, ArenaError::InvalidOpponent);
+
+    let pot = c.stake.checked_mul(2).ok_or(ArenaError::Overflow)?;
+    let fee = (pot as u128 * ctx.accounts.config.challenge_fee_bps as u128 / BPS_100) as u64;
+    let info = c.to_account_info();
+    move_lamports(&info, &ctx.accounts.revenue_wallet.to_account_info(), fee)?;
+    move_lamports(&info, &ctx.accounts.winner.to_account_info(), pot - fee)?;
+    Ok(())
+}
+
+pub(crate) fn cancel_challenge(ctx: Context<CancelChallenge>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let c = &ctx.accounts.challenge;
+    require!(c.status == ChallengeStatus::Open as u8, ArenaError::ChallengeNotOpen);
+    require!(
+        ctx.accounts.signer.key() == c.creator || Clock::get()?.unix_timestamp > c.accept_deadline,
+        ArenaError::ChallengeNotExpired
+    );
+    // Closing returns the stake together with the rent
+    Ok(())
+}
+
+pub(crate) fn refund_challenge(ctx: Context<RefundChallenge>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let c = &ctx.accounts.challenge;
+    require!(c.status == ChallengeStatus::Active as u8, ArenaError::ChallengeNotActive);
+    let abandoned_at = c.ends_at.checked_add(CHALLENGE_SETTLE_GRACE_SECS).ok_or(ArenaError::Overflow)?;
+    require!(Clock::get()?.unix_timestamp >= abandoned_at, ArenaError::ChallengeNotAbandoned);
+    // The opponent's stake goes back now; closing returns the creator's with the rent
+    move_lamports(&c.to_account_info(), &ctx.accounts.opponent.to_account_info(), c.stake)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge_deposits_stop_while_paused() {
+        let mut config = ArenaConfig { min_ticket_lamports: 1_000, ..Default::default() };
+        let (creator, opponent) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let open = Challenge { creator, stake: 1_000, accept_deadline: 100, status: ChallengeStatus::Open as u8, ..Default::default() };
+        assert!(check_new_challenge(&config, &creator, &opponent, 1_000, 3_600, 3_600).is_ok());
+        assert!(check_accept(&config, &open, &opponent, 50).is_ok());
+
+        config.paused_flags = PAUSE_DEPOSIT;
+        assert!(check_new_challenge(&config, &creator, &opponent, 1_000, 3_600, 3_600).is_err());
+        assert!(check_accept(&config, &open, &opponent, 50).is_err());
+        // Payout bits leave deposits open
+        config.paused_flags = PAUSE_CLAIM;
+        assert!(check_accept(&config, &open, &opponent, 50).is_ok());
+    }
+}
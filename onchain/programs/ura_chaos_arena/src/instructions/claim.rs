@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+    #[account(mut, seeds = [b"vault", match_state.key().as_ref()], bump)]
+    pub match_vault: SystemAccount<'info>,
+    #[account(mut, seeds = [b"alloc", match_state.key().as_ref(), winner.key().as_ref()], bump = allocation.bump)]
+    pub allocation: Account<'info, WinnerAllocation>,
+    #[account(mut, seeds = [b"profile", winner.key().as_ref()], bump = profile.bump)]
+    pub profile: Account<'info, PlayerProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn claim(ctx: Context<Claim>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let m = &ctx.accounts.match_state;
+    let alloc = &mut ctx.accounts.allocation;
+    require!(!alloc.claimed, ArenaError::AlreadyClaimed);
+    if m.stake_weight_cap > 0 {
+        require!(m.allocations_recorded >= m.winners_count, ArenaError::AllocationsPending);
+        let share = rank_share_ppt(m.winners_count, m.group2_count, m.group3_count, alloc.rank).map_err(ArenaError::from)?;
+        let score = stake_score(share, alloc.weight).map_err(ArenaError::from)?;
+        alloc.amount = stake_weighted_allocation(m.prize_pool, score, m.total_stake_score).map_err(ArenaError::from)?;
+    }
+    let amount = alloc.amount;
+    require!(amount > 0, ArenaError::ZeroAllocation);
+    require!(alloc.player == ctx.accounts.winner.key(), ArenaError::InvalidAllocationOwner);
+
+    // Transfer from vault PDA to winner
+    let vault_bump = ctx.bumps.match_vault;
+    let vault_seeds: &[&[u8]] = &[b"vault", &ctx.accounts.match_state.key().to_bytes(), &[vault_bump]];
+    transfer_from_vault(
+        &ctx.accounts.match_vault,
+        &ctx.accounts.winner,
+        &ctx.accounts.system_program,
+        amount,
+        &[vault_seeds],
+    )?;
+
+    alloc.claimed = true;
+    let profile = &mut ctx.accounts.profile;
+    profile.prize_lamports = profile.prize_lamports.checked_add(amount).ok_or(ArenaError::Overflow)?;
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"config"],
+        bump,
+        space = 8 + ArenaConfig::SIZE,
+    )]
+    pub config: Account<'info, ArenaConfig>,
+    // Stats PDA holding global counters
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"stats", config.key().as_ref()],
+        bump,
+        space = 8 + Stats::SIZE,
+    )]
+    pub stats: Account<'info, Stats>,
+    // Buyback vault PDAs to accumulate SOL for later off-chain buyback and burn operations
+    #[account(
+        mut,
+        seeds = [b"buyback_ura", config.key().as_ref()],
+        bump,
+    )]
+    pub buyback_ura_vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"buyback_urac", config.key().as_ref()],
+        bump,
+    )]
+    pub buyback_urac_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    #[account(constraint = signer.key() == config.authority || signer.key() == config.guardian @ ArenaError::NotAuthorityOrGuardian)]
+    pub signer: Signer<'info>,
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+}
+
+pub(crate) fn initialize_config(
+    ctx: Context<InitializeConfig>,
+    revenue_wallet: Pubkey,
+    pyth_feed_id: [u8; 32],
+    min_ticket_lamports: u64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.authority = ctx.accounts.authority.key();
+    config.revenue_wallet = revenue_wallet;
+    config.pyth_feed_id = pyth_feed_id;
+    config.min_ticket_lamports = min_ticket_lamports;
+    config.oracle_policy = OraclePolicy::Strict as u8;
+    config.pyth_max_age_secs = PYTH_STALENESS_DEFAULT;
+    config.pyth_max_conf_bps = PYTH_CONF_BPS_DEFAULT;
+    config.admin_timelock_secs = ADMIN_TIMELOCK_DEFAULT_SECS;
+    config.bump = ctx.bumps.config;
+    config.version = ACCOUNT_VERSION;
+    config.buyback_ura_bump = ctx.bumps.buyback_ura_vault;
+    config.buyback_urac_bump = ctx.bumps.buyback_urac_vault;
+
+    fund_vault_rent(&ctx.accounts.authority, &ctx.accounts.buyback_ura_vault, &ctx.accounts.system_program)?;
+    fund_vault_rent(&ctx.accounts.authority, &ctx.accounts.buyback_urac_vault, &ctx.accounts.system_program)?;
+
+    // Initialize stats (zero-initialization is automatic)
+    ctx.accounts.stats.bump = ctx.bumps.stats;
+    ctx.accounts.stats.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn set_join_grace(ctx: Context<UpdateConfig>, grace_secs: u32) -> Result<()> {
+    require!(grace_secs <= JOIN_GRACE_MAX_SECS, ArenaError::InvalidJoinGrace);
+    ctx.accounts.config.join_grace_secs = grace_secs;
+    Ok(())
+}
+
+pub(crate) fn pause(ctx: Context<Pause>, flags: u8) -> Result<()> {
+    add_pause_flags(&mut ctx.accounts.config, flags)
+}
+
+// The guardian's only power: set more bits, never clear one.
+fn add_pause_flags(config: &mut ArenaConfig, flags: u8) -> Result<()> {
+    require!(flags != 0 && flags & !PAUSE_ALL == 0, ArenaError::InvalidPauseFlags);
+    config.paused_flags |= flags;
+    Ok(())
+}
+
+pub(crate) fn set_paused_flags(ctx: Context<UpdateConfig>, flags: u8) -> Result<()> {
+    require!(flags & !PAUSE_ALL == 0, ArenaError::InvalidPauseFlags);
+    ctx.accounts.config.paused_flags = flags;
+    Ok(())
+}
+
+pub(crate) fn set_oracle_policy(
+    ctx: Context<UpdateConfig>,
+    policy: u8,
+    max_age_secs: u64,
+    max_conf_bps: u16,
+) -> Result<()> {
+    let policy = OraclePolicy::from_u8(policy).ok_or(ArenaError::InvalidOraclePolicy)?;
+    require!(
+        (PYTH_STALENESS_MIN..=PYTH_STALENESS_MAX).contains(&max_age_secs),
+        ArenaError::InvalidOracleThreshold
+    );
+    require!(
+        (PYTH_CONF_BPS_MIN..=PYTH_CONF_BPS_MAX).contains(&max_conf_bps),
+        ArenaError::InvalidOracleThreshold
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.oracle_policy = policy as u8;
+    config.pyth_max_age_secs = max_age_secs;
+    config.pyth_max_conf_bps = max_conf_bps;
+    Ok(())
+}
+
+pub(crate) fn set_pyth_feed(ctx: Context<UpdateConfig>, feed_id: [u8; 32]) -> Result<()> {
+    require!(feed_id != [0; 32], ArenaError::PythFeedUnset);
+    ctx.accounts.config.pyth_feed_id = feed_id;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::require_not_paused;
+
+    #[test]
+    fn pause_only_adds_bits() {
+        let mut config = ArenaConfig::default();
+        add_pause_flags(&mut config, PAUSE_CLAIM).unwrap();
+        add_pause_flags(&mut config, PAUSE_JOIN).unwrap();
+        assert_eq!(config.paused_flags, PAUSE_CLAIM | PAUSE_JOIN);
+        // Re-sending a bit keeps it set; empty or unknown masks are rejected
+        add_pause_flags(&mut config, PAUSE_CLAIM).unwrap();
+        assert!(add_pause_flags(&mut config, 0).is_err());
+        assert!(add_pause_flags(&mut config, PAUSE_ALL + 1).is_err());
+        assert_eq!(config.paused_flags, PAUSE_CLAIM | PAUSE_JOIN);
+
+        assert!(require_not_paused(&config, PAUSE_CLAIM).is_err());
+        assert!(require_not_paused(&config, PAUSE_FINALIZE).is_ok());
+        // Result-recording paths check two bits and stop if either is set
+        assert!(require_not_paused(&config, PAUSE_ALLOCATE | PAUSE_CLAIM).is_err());
+        add_pause_flags(&mut config, PAUSE_ALL).unwrap();
+        assert!(require_not_paused(&config, PAUSE_BURN).is_err());
+    }
+}
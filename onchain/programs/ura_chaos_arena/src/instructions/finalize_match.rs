@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct FinalizeMatch<'info> {
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"stats", config.key().as_ref()], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+    #[account(mut, seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+    #[account(mut, seeds = [b"vault", match_state.key().as_ref()], bump)]
+    pub match_vault: SystemAccount<'info>,
+    #[account(mut, seeds = [b"buyback_ura", config.key().as_ref()], bump = config.buyback_ura_bump)]
+    pub buyback_ura_vault: SystemAccount<'info>,
+    #[account(mut, seeds = [b"buyback_urac", config.key().as_ref()], bump = config.buyback_urac_bump)]
+    pub buyback_urac_vault: SystemAccount<'info>,
+    /// CHECK: revenue wallet can be any system account
+    #[account(mut, address = config.revenue_wallet)]
+    pub revenue_wallet: UncheckedAccount<'info>,
+    // Required once staking is enabled
+    #[account(mut, seeds = [b"staking", config.key().as_ref()], bump = staking_pool.bump)]
+    pub staking_pool: Option<Account<'info, StakingPool>>,
+    // Required for tournament matches
+    #[account(mut, seeds = [b"tournament", match_state.tier.as_ref()], bump = tournament.bump)]
+    pub tournament: Option<Account<'info, Tournament>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn finalize_match(ctx: Context<FinalizeMatch>, day_id: i64) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_FINALIZE)?;
+    let clock = Clock::get()?;
+    // You can only finalize a day whose end has passed, plus the join grace window so no
+    // late join can still land after the pot is split.
+    require!(clock.unix_timestamp >= last_join_at(&ctx.accounts.config, day_id), ArenaError::TooEarlyToFinalize);
+
+    let match_key = ctx.accounts.match_state.key();
+    let m = &mut ctx.accounts.match_state;
+    require!(m.day_id == day_id, ArenaError::WrongMatchForDay);
+    require!(m.status == MatchStatus::Open as u8, ArenaError::MatchAlreadyFinalized);
+
+    let pot = m.pot_lamports;
+    require!(pot > 0, ArenaError::EmptyPot);
+
+    // Buckets: 85% prize, 5% each for URA, URACHAOS, revenue
+    let mut remaining = pot;
+    let revenue = (pot as u128 * PCT_5) / PCT_100;
+    let ura = (pot as u128 * PCT_5) / PCT_100;
+    let urac = (pot as u128 * PCT_5) / PCT_100;
+    // Note: To avoid rounding dust staying in vault, we compute prize as the exact remaining
+    // after moving out the three 5% buckets.
+
+    let revenue = revenue as u64;
+    let ura = ura as u64;
+    let urac = urac as u64;
+
+    // Compute lamports to transfer out of the match vault now
+    let out_total = revenue.checked_add(ura).and_then(|x| x.checked_add(urac)).ok_or(ArenaError::Overflow)?;
+    remaining = remaining.checked_sub(out_total).ok_or(ArenaError::Overflow)?;
+    // Referral rebates were paid from the revenue bucket at join and never reached the vault
+    let mut revenue = revenue.checked_sub(m.referral_lamports).ok_or(ArenaError::Overflow)?;
+
+    // Transfers from match_vault (PDA) using its signer seeds
+    let vault_bump = ctx.bumps.match_vault;
+    let vault_seeds: &[&[u8]] = &[b"vault", &match_key.to_bytes(), &[vault_bump]];
+
+    // Tournament organizer's slice of the revenue bucket, credited to the tournament PDA
+    if m.organizer_bps > 0 {
+        let t = ctx.accounts.tournament.as_mut().ok_or(ArenaError::MissingTournament)?;
+        let cut = (revenue as u128 * m.organizer_bps as u128 / BPS_100) as u64;
+        if cut > 0 {
+            t.accrued_lamports = t.accrued_lamports.checked_add(cut).ok_or(ArenaError::Overflow)?;
+            t.total_earned = t.total_earned.checked_add(cut).ok_or(ArenaError::Overflow)?;
+            revenue -= cut;
+            transfer_from_vault(
+                &ctx.accounts.match_vault,
+                t,
+                &ctx.accounts.system_program,
+                cut,
+                &[vault_seeds],
+            )?;
+        }
+    }
+
+    // URACHAOS stakers' slice of the revenue bucket (kept by revenue while nobody stakes)
+    if ctx.accounts.config.staking_enabled {
+        let pool = ctx.accounts.staking_pool.as_mut().ok_or(ArenaError::MissingStakingPool)?;
+        let share = (revenue as u128 * pool.revenue_bps as u128 / BPS_100) as u64;
+        if share > 0 && pool.total_staked > 0 {
+            pool.acc_reward_per_share = pool
+                .acc_reward_per_share
+                .checked_add(share as u128 * REWARD_SCALE / pool.total_staked as u128)
+                .ok_or(ArenaError::Overflow)?;
+            pool.total_rewards = pool.total_rewards.saturating_add(share);
+            revenue -= share;
+            transfer_from_vault(
+                &ctx.accounts.match_vault,
+                pool,
+                &ctx.accounts.system_program,
+                share,
+                &[vault_seeds],
+            )?;
+        }
+    }
+
+    // revenue to provided wallet
+    transfer_from_vault(
+        &ctx.accounts.match_vault,
+        &ctx.accounts.revenue_wallet,
+        &ctx.accounts.system_program,
+        revenue,
+        &[vault_seeds],
+    )?;
+
+    // buyback URA to PDA vault (escrow to be swapped/burned off-chain)
+    transfer_from_vault(
+        &ctx.accounts.match_vault,
+        &ctx.accounts.buyback_ura_vault,
+        &ctx.accounts.system_program,
+        ura,
+        &[vault_seeds],
+    )?;
+
+    // buyback URACHAOS to its PDA vault
+    transfer_from_vault(
+        &ctx.accounts.match_vault,
+        &ctx.accounts.buyback_urac_vault,
+        &ctx.accounts.system_program,
+        urac,
+        &[vault_seeds],
+    )?;
+
+    // Derive winners and group sizes based on distinct players (re-entries don't add ranks).
+    let (winners_count, group2_count, group3_count) = winner_groups(m.player_count);
+
+    // Team arenas set aside the guild bucket from the prize pool
+    if m.guild_prize_bps > 0 && m.guild_count > 0 {
+        m.guild_pool = (remaining as u128 * m.guild_prize_bps as u128 / BPS_100) as u64;
+        remaining -= m.guild_pool;
+    }
+
+    m.status = MatchStatus::Finalized as u8;
+    m.winners_count = winners_count;
+    m.group2_count = group2_count;
+    m.group3_count = group3_count;
+    m.prize_pool = remaining; // exact amount left in vault for winner claims
+
+    // Precompute remainder to be added to rank 1 allocation later (rounding effect)
+    m.remainder = compute_remainder_for_top1(remaining, winners_count, group2_count, group3_count);
+
+    // Update global stats
+    let stats = &mut ctx.accounts.stats;
+    stats.total_matches = stats.total_matches.saturating_add(1);
+    stats.total_players = stats.total_players.saturating_add(m.player_count as u64);
+    stats.total_prize_distributed = stats.total_prize_distributed.saturating_add(remaining as u128);
+    stats.total_ura_earmarked_sol = stats.total_ura_earmarked_sol.saturating_add(ura as u128);
+    stats.total_urac_earmarked_sol = stats.total_urac_earmarked_sol.saturating_add(urac as u128);
+
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct CreateGuild<'info> {
+    #[account(mut)]
+    pub founder: Signer<'info>,
+    #[account(
+        init,
+        payer = founder,
+        seeds = [b"guild", founder.key().as_ref()],
+        bump,
+        space = 8 + Guild::SIZE,
+    )]
+    pub guild: Account<'info, Guild>,
+    #[account(
+        init,
+        payer = founder,
+        seeds = [b"member", founder.key().as_ref()],
+        bump,
+        space = 8 + GuildMember::SIZE,
+    )]
+    pub member: Account<'info, GuildMember>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGuild<'info> {
+    pub founder: Signer<'info>,
+    #[account(mut, seeds = [b"guild", founder.key().as_ref()], bump = guild.bump, has_one = founder)]
+    pub guild: Account<'info, Guild>,
+}
+
+#[derive(Accounts)]
+pub struct JoinGuild<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(mut, seeds = [b"guild", guild.founder.as_ref()], bump = guild.bump)]
+    pub guild: Account<'info, Guild>,
+    #[account(
+        init,
+        payer = player,
+        seeds = [b"member", player.key().as_ref()],
+        bump,
+        space = 8 + GuildMember::SIZE,
+    )]
+    pub member: Account<'info, GuildMember>,
+    pub founder: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveGuild<'info> {
+    pub signer: Signer<'info>,
+    #[account(mut, seeds = [b"guild", guild.founder.as_ref()], bump = guild.bump)]
+    pub guild: Account<'info, Guild>,
+    /// CHECK: member being removed; receives the roster entry's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"member", player.key().as_ref()],
+        bump = member.bump,
+        has_one = player,
+        has_one = guild,
+        close = player,
+    )]
+    pub member: Account<'info, GuildMember>,
+}
+
+#[derive(Accounts)]
+#[instruction(day_id: i64)]
+pub struct OpenGuildStanding<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    // Any tier, see Join::tier
+    pub tier: Account<'info, ArenaTier>,
+    #[account(seeds = [b"member", player.key().as_ref()], bump = member.bump)]
+    pub member: Account<'info, GuildMember>,
+    #[account(
+        init,
+        payer = player,
+        seeds = [b"standing", tier.key().as_ref(), &day_id.to_le_bytes(), member.guild.as_ref()],
+        bump,
+        space = 8 + GuildStanding::SIZE,
+    )]
+    pub standing: Account<'info, GuildStanding>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitGuildResults<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimGuildPrize<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+    #[account(mut, seeds = [b"vault", match_state.key().as_ref()], bump)]
+    pub match_vault: SystemAccount<'info>,
+    #[account(mut, seeds = [b"entry", match_state.key().as_ref(), player.key().as_ref()], bump = entry.bump)]
+    pub entry: Account<'info, PlayerEntry>,
+    #[account(
+        seeds = [b"standing", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes(), entry.guild.as_ref()],
+        bump = standing.bump,
+    )]
+    pub standing: Account<'info, GuildStanding>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn create_guild(ctx: Context<CreateGuild>, name: [u8; 32], open: bool) -> Result<()> {
+    let g = &mut ctx.accounts.guild;
+    g.founder = ctx.accounts.founder.key();
+    g.name = name;
+    g.open = open;
+    g.member_count = 1;
+    g.bump = ctx.bumps.guild;
+    g.version = ACCOUNT_VERSION;
+
+    let member = &mut ctx.accounts.member;
+    member.guild = g.key();
+    member.player = g.founder;
+    member.joined_at = Clock::get()?.unix_timestamp;
+    member.bump = ctx.bumps.member;
+    member.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn set_guild_open(ctx: Context<UpdateGuild>, open: bool) -> Result<()> {
+    ctx.accounts.guild.open = open;
+    Ok(())
+}
+
+pub(crate) fn join_guild(ctx: Context<JoinGuild>) -> Result<()> {
+    let g = &mut ctx.accounts.guild;
+    if !g.open {
+        let founder = ctx.accounts.founder.as_ref().ok_or(ArenaError::GuildClosed)?;
+        require_keys_eq!(founder.key(), g.founder, ArenaError::GuildClosed);
+    }
+    require!(g.member_count < GUILD_MAX_MEMBERS, ArenaError::GuildFull);
+    g.member_count += 1;
+
+    let member = &mut ctx.accounts.member;
+    member.guild = g.key();
+    member.player = ctx.accounts.player.key();
+    member.joined_at = Clock::get()?.unix_timestamp;
+    member.bump = ctx.bumps.member;
+    member.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn leave_guild(ctx: Context<LeaveGuild>) -> Result<()> {
+    let g = &mut ctx.accounts.guild;
+    let signer = ctx.accounts.signer.key();
+    let player = ctx.accounts.player.key();
+    require!(signer == player || signer == g.founder, ArenaError::NotGuildMemberOrFounder);
+    require!(player != g.founder, ArenaError::FounderCannotLeave);
+    g.member_count = g.member_count.saturating_sub(1);
+    Ok(())
+}
+
+pub(crate) fn open_guild_standing(ctx: Context<OpenGuildStanding>, day_id: i64) -> Result<()> {
+    require!(ctx.accounts.tier.guild_prize_bps > 0, ArenaError::NotTeamArena);
+    let st = &mut ctx.accounts.standing;
+    st.tier = ctx.accounts.tier.key();
+    st.day_id = day_id;
+    st.guild = ctx.accounts.member.guild;
+    st.bump = ctx.bumps.standing;
+    st.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn commit_guild_results<'c: 'info, 'info>(ctx: Context<'_, '_, 'c, 'info, CommitGuildResults<'info>>) -> Result<()> {
+    // Fixes which guilds get paid, so it stops with allocations and claims
+    require_not_paused(&ctx.accounts.config, PAUSE_ALLOCATE | PAUSE_CLAIM)?;
+    let m = &mut ctx.accounts.match_state;
+    require!(m.status == MatchStatus::Finalized as u8, ArenaError::MatchNotFinalized);
+    require!(m.guild_prize_bps > 0, ArenaError::NotTeamArena);
+    require!(!m.guild_results_committed, ArenaError::GuildResultsCommitted);
+    require!(m.allocations_recorded >= m.winners_count, ArenaError::AllocationsPending);
+    require!(ctx.remaining_accounts.len() == m.guild_count as usize, ArenaError::GuildStandingsIncomplete);
+
+    let mut standings = Vec::with_capacity(ctx.remaining_accounts.len());
+    for info in ctx.remaining_accounts.iter() {
+        let st: Account<GuildStanding> = Account::try_from(info)?;
+        require!(st.tier == m.tier && st.day_id == m.day_id && st.members > 0, ArenaError::GuildStandingsIncomplete);
+        require!(standings.iter().all(|o: &Account<GuildStanding>| o.key() != st.key()), ArenaError::GuildStandingsIncomplete);
+        standings.push(st);
+    }
+    standings.sort_by(|a, b| {
+        b.score.cmp(&a.score).then(a.members.cmp(&b.members)).then(a.guild.cmp(&b.guild))
+    });
+    for (i, st) in standings.iter_mut().enumerate().take(GUILD_RANK_PCT.len()) {
+        st.rank = i as u32 + 1;
+        st.exit(&crate::ID)?;
+    }
+    m.guild_results_committed = true;
+    Ok(())
+}
+
+pub(crate) fn claim_guild_prize(ctx: Context<ClaimGuildPrize>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let m = &ctx.accounts.match_state;
+    require!(m.guild_results_committed, ArenaError::GuildResultsPending);
+    let st = &ctx.accounts.standing;
+    require!(st.rank >= 1, ArenaError::ZeroAllocation);
+    let entry = &mut ctx.accounts.entry;
+    require!(!entry.guild_claimed, ArenaError::AlreadyClaimed);
+    entry.guild_claimed = true;
+
+    let amount = guild_member_share(m.guild_pool, m.guild_count, st.rank, st.members).map_err(ArenaError::from)?;
+    require!(amount > 0, ArenaError::ZeroAllocation);
+    let vault_bump = ctx.bumps.match_vault;
+    let vault_seeds: &[&[u8]] = &[b"vault", &ctx.accounts.match_state.key().to_bytes(), &[vault_bump]];
+    transfer_from_vault(
+        &ctx.accounts.match_vault,
+        &ctx.accounts.player,
+        &ctx.accounts.system_program,
+        amount,
+        &[vault_seeds],
+    )
+}
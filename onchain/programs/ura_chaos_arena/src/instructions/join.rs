@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+#[instruction(day_id: i64)]
+pub struct Join<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    // Standard tiers sit at [b"tier", [tier_id]] and tournament tiers at [b"tier", organizer, id],
+    // so the address is not re-derived here; only this program creates ArenaTier accounts.
+    pub tier: Account<'info, ArenaTier>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        seeds = [b"match", tier.key().as_ref(), day_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + MatchState::SIZE,
+    )]
+    pub match_state: Account<'info, MatchState>,
+    #[account(
+        mut,
+        seeds = [b"vault", match_state.key().as_ref()],
+        bump,
+    )]
+    pub match_vault: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        seeds = [b"entry", match_state.key().as_ref(), player.key().as_ref()],
+        bump,
+        space = 8 + PlayerEntry::SIZE,
+    )]
+    pub entry: Account<'info, PlayerEntry>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+        space = 8 + PlayerProfile::SIZE,
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    // Referrer's ReferralAccount: binds on a new player's first match, then required for
+    // every ticket of a referred player
+    #[account(mut, seeds = [b"referral", referral.referrer.as_ref()], bump = referral.bump)]
+    pub referral: Option<Account<'info, ReferralAccount>>,
+    // Team arenas: the player's roster entry and their guild's standing for this day
+    #[account(seeds = [b"member", player.key().as_ref()], bump = guild_member.bump)]
+    pub guild_member: Option<Account<'info, GuildMember>>,
+    #[account(
+        mut,
+        seeds = [b"standing", guild_standing.tier.as_ref(), &guild_standing.day_id.to_le_bytes(), guild_standing.guild.as_ref()],
+        bump = guild_standing.bump,
+    )]
+    pub guild_standing: Option<Account<'info, GuildStanding>>,
+    // Holder-only tiers: the player's token account for the gate mint (or the NFT), and for
+    // collection gates the NFT's Metaplex metadata and its mint
+    pub gate_tokens: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: address and owner checked in require_token_gate
+    pub gate_metadata: Option<UncheckedAccount<'info>>,
+    pub gate_nft_mint: Option<InterfaceAccount<'info, Mint>>,
+    // Tournament tiers: the player's invite from register_tournament
+    #[account(seeds = [b"invite", invite.tournament.as_ref(), player.key().as_ref()], bump = invite.bump)]
+    pub invite: Option<Account<'info, TournamentInvite>>,
+    /// CHECK: the player's BetMark in this match; must not exist
+    #[account(seeds = [b"bet_mark", match_state.key().as_ref(), player.key().as_ref()], bump)]
+    pub bet_mark: UncheckedAccount<'info>,
+    /// CHECK: Pyth PriceUpdateV2 account; owner checked here, feed id and freshness in load_price_update.
+    /// May be omitted while the oracle policy is EmergencyFixedPrice.
+    #[account(owner = pyth_receiver::ID)]
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Join<'info> {
+    // Every entry path (join, join_exact, join_future, redeem_pass) takes these accounts and
+    // reaches enter_match through here. Pass redemptions pay from the tier's pass vault.
+    pub(crate) fn entry_accounts<'a>(
+        &'a mut self,
+        bumps: &JoinBumps,
+        pass_vault: Option<(&'a SystemAccount<'info>, u8)>,
+    ) -> EntryAccounts<'a, 'info> {
+        EntryAccounts {
+            player: &self.player,
+            tier: &self.tier,
+            match_state: &mut self.match_state,
+            match_vault: &self.match_vault,
+            entry: &mut self.entry,
+            profile: &mut self.profile,
+            system_program: &self.system_program,
+            match_bump: bumps.match_state,
+            entry_bump: bumps.entry,
+            profile_bump: bumps.profile,
+            pass_vault,
+            referral: self.referral.as_mut(),
+            referral_bps: self.config.referral_bps,
+            organizer_bps: self.config.organizer_bps,
+            guild_member: self.guild_member.as_ref(),
+            guild_standing: self.guild_standing.as_mut(),
+            gate_tokens: self.gate_tokens.as_ref(),
+            gate_metadata: self.gate_metadata.as_ref(),
+            gate_nft_mint: self.gate_nft_mint.as_ref(),
+            invite: self.invite.as_ref(),
+            bet_mark: &self.bet_mark,
+        }
+    }
+}
+
+pub(crate) fn join(ctx: Context<Join>, day_id: i64, amount: u64) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_JOIN)?;
+    require!(amount > 0, ArenaError::InvalidAmount);
+    let config = &ctx.accounts.config;
+    let tier = &ctx.accounts.tier;
+    let clock = Clock::get()?;
+    let min_lamports = ticket_lamports(config, tier, Some(&ctx.accounts.match_state), ctx.accounts.price_update.as_ref(), clock.unix_timestamp)?;
+    require!(amount >= min_lamports, ArenaError::TicketTooCheap);
+    // Optional safety floor
+    require!(amount >= config.min_ticket_lamports, ArenaError::TicketTooCheap);
+
+    let ticket = min_lamports.max(config.min_ticket_lamports);
+    let at = entry_time(config, tier, day_id, clock.unix_timestamp)?;
+    enter_match(ctx.accounts.entry_accounts(&ctx.bumps, None), day_id, amount, ticket, at, clock.unix_timestamp)
+}
+
+pub(crate) fn join_exact(ctx: Context<Join>, day_id: i64, max_lamports: u64) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_JOIN)?;
+    let config = &ctx.accounts.config;
+    let tier = &ctx.accounts.tier;
+    let clock = Clock::get()?;
+    let price = ticket_lamports(config, tier, Some(&ctx.accounts.match_state), ctx.accounts.price_update.as_ref(), clock.unix_timestamp)?
+        .max(config.min_ticket_lamports);
+    require!(price > 0, ArenaError::InvalidAmount);
+    require!(price <= max_lamports, ArenaError::TicketPriceAboveMax);
+
+    let at = entry_time(config, tier, day_id, clock.unix_timestamp)?;
+    enter_match(ctx.accounts.entry_accounts(&ctx.bumps, None), day_id, price, price, at, clock.unix_timestamp)
+}
+
+pub(crate) fn join_future(ctx: Context<Join>, day_id: i64, amount: u64) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_JOIN)?;
+    require!(amount > 0, ArenaError::InvalidAmount);
+    let config = &ctx.accounts.config;
+    let tier = &ctx.accounts.tier;
+    let clock = Clock::get()?;
+    let today = unix_day(clock.unix_timestamp);
+    require!(
+        day_id > today && day_id <= today + tier.max_days_ahead as i64,
+        ArenaError::InvalidFutureDay
+    );
+    let min_lamports = ticket_lamports(config, tier, Some(&ctx.accounts.match_state), ctx.accounts.price_update.as_ref(), clock.unix_timestamp)?;
+    require!(amount >= min_lamports, ArenaError::TicketTooCheap);
+    require!(amount >= config.min_ticket_lamports, ArenaError::TicketTooCheap);
+
+    let ticket = min_lamports.max(config.min_ticket_lamports);
+    enter_match(ctx.accounts.entry_accounts(&ctx.bumps, None), day_id, amount, ticket, clock.unix_timestamp, clock.unix_timestamp)
+}
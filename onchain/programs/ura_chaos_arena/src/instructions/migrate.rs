@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: owner and discriminator checked in migrate_layout
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Dispatches on the account type. The numbering is part of the instruction's interface: new
+// account types take the next free number.
+pub(crate) fn migrate(a: &MigrateAccount, kind: u8) -> Result<()> {
+    match kind {
+        0 => migrate_layout::<ArenaConfig>(a),
+        1 => migrate_layout::<Stats>(a),
+        2 => migrate_layout::<PendingAdminAction>(a),
+        3 => migrate_layout::<ArenaTier>(a),
+        4 => migrate_layout::<MatchState>(a),
+        5 => migrate_layout::<PlayerEntry>(a),
+        6 => migrate_layout::<PlayerProfile>(a),
+        7 => migrate_layout::<ReferralAccount>(a),
+        8 => migrate_layout::<Tournament>(a),
+        9 => migrate_layout::<TournamentInvite>(a),
+        10 => migrate_layout::<Guild>(a),
+        11 => migrate_layout::<GuildMember>(a),
+        12 => migrate_layout::<GuildStanding>(a),
+        13 => migrate_layout::<Challenge>(a),
+        14 => migrate_layout::<StakingPool>(a),
+        15 => migrate_layout::<StakePosition>(a),
+        16 => migrate_layout::<PredictionPool>(a),
+        17 => migrate_layout::<PredictionOutcome>(a),
+        18 => migrate_layout::<PredictionBet>(a),
+        19 => migrate_layout::<Achievement>(a),
+        20 => migrate_layout::<AchievementUnlock>(a),
+        21 => migrate_layout::<SeasonPass>(a),
+        22 => migrate_layout::<WaitlistEntry>(a),
+        23 => migrate_layout::<WinnerAllocation>(a),
+        24 => migrate_layout::<BetMark>(a),
+        _ => err!(ArenaError::InvalidMigration),
+    }
+}
+
+// Grows `account` to T's current size (fields added since it was written read as zero, unless
+// T::fill_defaults sets them), tops up its rent from the payer and stamps ACCOUNT_VERSION.
+// Fails if it is already current.
+fn migrate_layout<'info, T>(a: &MigrateAccount<'info>) -> Result<()>
+where
+    T: Versioned + AccountSerialize + AccountDeserialize + anchor_lang::Discriminator,
+{
+    let info = a.account.to_account_info();
+    require_keys_eq!(*info.owner, crate::ID, ArenaError::InvalidMigration);
+    require!(
+        info.data_len() >= 8 && info.try_borrow_data()?[..8] == T::DISCRIMINATOR,
+        ArenaError::InvalidMigration
+    );
+    require!(info.data_len() <= T::SPACE, ArenaError::InvalidMigration);
+
+    if info.data_len() < T::SPACE {
+        let top_up = Rent::get()?.minimum_balance(T::SPACE).saturating_sub(info.lamports());
+        if top_up > 0 {
+            invoke(
+                &system_instruction::transfer(&a.payer.key(), &info.key(), top_up),
+                &[a.payer.to_account_info(), info.clone(), a.system_program.to_account_info()],
+            )?;
+        }
+        info.realloc(T::SPACE, true)?;
+    }
+
+    let mut account = T::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    let from_version = *account.version_mut();
+    require!(from_version < ACCOUNT_VERSION, ArenaError::AlreadyMigrated);
+    *account.version_mut() = ACCOUNT_VERSION;
+    account.fill_defaults(from_version);
+    account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+mod achievement;
+mod admin;
+mod challenge;
+mod claim;
+mod config;
+mod finalize_match;
+mod guild;
+mod join;
+mod migrate;
+mod pass;
+mod prediction;
+mod record_allocation;
+mod record_burned;
+mod referral;
+mod staking;
+mod tier;
+mod tournament;
+mod waitlist;
+
+pub use achievement::*;
+pub use admin::*;
+pub use challenge::*;
+pub use claim::*;
+pub use config::*;
+pub use finalize_match::*;
+pub use guild::*;
+pub use join::*;
+pub use migrate::*;
+pub use pass::*;
+pub use prediction::*;
+pub use record_allocation::*;
+pub use record_burned::*;
+pub use referral::*;
+pub use staking::*;
+pub use tier::*;
+pub use tournament::*;
+pub use waitlist::*;
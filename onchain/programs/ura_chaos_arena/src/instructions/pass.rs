@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+use crate::utils::*;
+use super::join::*;
+
+#[derive(Accounts)]
+pub struct BuyPass<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    // Any tier, see Join::tier
+    pub tier: Account<'info, ArenaTier>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        seeds = [b"pass", tier.key().as_ref(), player.key().as_ref()],
+        bump,
+        space = 8 + SeasonPass::SIZE,
+    )]
+    pub pass: Account<'info, SeasonPass>,
+    #[account(mut, seeds = [b"pass_vault", tier.key().as_ref()], bump)]
+    pub pass_vault: SystemAccount<'info>,
+    /// CHECK: see Join::price_update
+    #[account(owner = pyth_receiver::ID)]
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemPass<'info> {
+    // The accounts of any join; price_update may be omitted
+    pub join: Join<'info>,
+    #[account(
+        mut,
+        seeds = [b"pass", join.tier.key().as_ref(), join.player.key().as_ref()],
+        bump = pass.bump,
+    )]
+    pub pass: Account<'info, SeasonPass>,
+    #[account(mut, seeds = [b"pass_vault", join.tier.key().as_ref()], bump)]
+    pub pass_vault: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundPass<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        mut,
+        seeds = [b"pass", pass.tier.as_ref(), player.key().as_ref()],
+        bump = pass.bump,
+        has_one = player,
+        close = player,
+    )]
+    pub pass: Account<'info, SeasonPass>,
+    #[account(mut, seeds = [b"pass_vault", pass.tier.as_ref()], bump)]
+    pub pass_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// A pass covers the day it is bought on and the following days - 1 days.
+fn pass_expiry(now: i64, days: u16) -> i64 {
+    (unix_day(now) + days as i64) * SECONDS_PER_DAY
+}
+
+// Value of the credits left on an expired pass, which go back to the player.
+fn unused_credit_lamports(pass: &SeasonPass, now: i64) -> Result<u64> {
+    require!(now >= pass.expires_at, ArenaError::PassStillActive);
+    pass.lamports_per_credit.checked_mul(pass.credits as u64).ok_or(error!(ArenaError::Overflow))
+}
+
+pub(crate) fn buy_pass(ctx: Context<BuyPass>, days: u16, max_lamports: u64) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_JOIN)?;
+    require!(PASS_SIZES_DAYS.contains(&days), ArenaError::InvalidPassSize);
+    let config = &ctx.accounts.config;
+    let tier = &ctx.accounts.tier;
+    let clock = Clock::get()?;
+    let ticket = ticket_lamports(config, tier, None, ctx.accounts.price_update.as_ref(), clock.unix_timestamp)?
+        .max(config.min_ticket_lamports);
+    let per_credit = ceil_div_u128(
+        ticket as u128 * (BPS_100 - tier.pass_discount_bps as u128),
+        BPS_100,
+    ).map_err(ArenaError::from)? as u64;
+    require!(per_credit > 0, ArenaError::InvalidAmount);
+    let price = per_credit.checked_mul(days as u64).ok_or(ArenaError::Overflow)?;
+    require!(price <= max_lamports, ArenaError::TicketPriceAboveMax);
+
+    // Renewing an expired pass first refunds its unused credits
+    let pass = &mut ctx.accounts.pass;
+    if pass.credits > 0 {
+        require_not_paused(config, PAUSE_CLAIM)?;
+        let refund = unused_credit_lamports(pass, clock.unix_timestamp)?;
+        let tier_key = tier.key();
+        let vault_seeds: &[&[u8]] = &[b"pass_vault", tier_key.as_ref(), &[ctx.bumps.pass_vault]];
+        transfer_from_vault(&ctx.accounts.pass_vault, &ctx.accounts.player, &ctx.accounts.system_program, refund, &[vault_seeds])?;
+    }
+    pass.player = ctx.accounts.player.key();
+    pass.tier = tier.key();
+    pass.credits = days;
+    pass.lamports_per_credit = per_credit;
+    pass.purchased_at = clock.unix_timestamp;
+    pass.expires_at = pass_expiry(clock.unix_timestamp, days);
+    pass.bump = ctx.bumps.pass;
+    pass.version = ACCOUNT_VERSION;
+
+    fund_vault_rent(&ctx.accounts.player, &ctx.accounts.pass_vault, &ctx.accounts.system_program)?;
+    let ix = system_instruction::transfer(&ctx.accounts.player.key(), &ctx.accounts.pass_vault.key(), price);
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.player.to_account_info(),
+            ctx.accounts.pass_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        &[],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn redeem_pass(ctx: Context<RedeemPass>, day_id: i64) -> Result<()> {
+    require_not_paused(&ctx.accounts.join.config, PAUSE_JOIN)?;
+    let clock = Clock::get()?;
+    let pass = &mut ctx.accounts.pass;
+    require!(pass.credits > 0, ArenaError::NoPassCredits);
+    require!(clock.unix_timestamp < pass.expires_at, ArenaError::PassExpired);
+    pass.credits -= 1;
+    let amount = pass.lamports_per_credit;
+
+    let RedeemPass { join, pass_vault, .. } = ctx.accounts;
+    let at = entry_time(&join.config, &join.tier, day_id, clock.unix_timestamp)?;
+    let entry_accounts = join.entry_accounts(&ctx.bumps.join, Some((pass_vault, ctx.bumps.pass_vault)));
+    enter_match(entry_accounts, day_id, amount, amount, at, clock.unix_timestamp)
+}
+
+pub(crate) fn refund_pass(ctx: Context<RefundPass>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let pass = &ctx.accounts.pass;
+    let refund = unused_credit_lamports(pass, Clock::get()?.unix_timestamp)?;
+    let vault_seeds: &[&[u8]] = &[b"pass_vault", pass.tier.as_ref(), &[ctx.bumps.pass_vault]];
+    // Closing the pass returns its rent as well
+    transfer_from_vault(&ctx.accounts.pass_vault, &ctx.accounts.player, &ctx.accounts.system_program, refund, &[vault_seeds])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_expire_and_refund_unused_credits() {
+        let day = 20_000;
+        let bought = day * SECONDS_PER_DAY + 15 * 3_600;
+        let pass = SeasonPass { credits: 3, lamports_per_credit: 1_000, purchased_at: bought, expires_at: pass_expiry(bought, 7), ..Default::default() };
+        // A 7-day pass covers the purchase day and the six after it
+        assert_eq!(pass.expires_at, (day + 7) * SECONDS_PER_DAY);
+        assert_eq!(unused_credit_lamports(&pass, pass.expires_at - 1).err(), Some(ArenaError::PassStillActive.into()));
+        assert_eq!(unused_credit_lamports(&pass, pass.expires_at).unwrap(), 3_000);
+        let used_up = SeasonPass { credits: 0, ..pass };
+        assert_eq!(unused_credit_lamports(&used_up, pass.expires_at).unwrap(), 0);
+    }
+
+    #[test]
+    fn last_refund_leaves_the_vault_rent_exempt() {
+        let rent = Rent::default().minimum_balance(0);
+        let bought = 20_000 * SECONDS_PER_DAY;
+        // First purchase, priced below the rent minimum: the buyer funds the reserve first
+        let mut vault = vault_rent_shortfall(0, rent);
+        let mut weekly = SeasonPass { credits: 7, lamports_per_credit: 100, expires_at: pass_expiry(bought, 7), ..Default::default() };
+        vault += 700;
+        assert!(vault >= rent);
+        assert_eq!(vault_rent_shortfall(vault, rent), 0);
+        let monthly = SeasonPass { credits: 30, lamports_per_credit: 90, expires_at: pass_expiry(bought, 30), ..Default::default() };
+        vault += 2_700;
+        // Four redemptions, then both passes refunded after expiry
+        weekly.credits -= 4;
+        vault -= 4 * weekly.lamports_per_credit;
+        vault -= unused_credit_lamports(&weekly, weekly.expires_at).unwrap();
+        vault -= unused_credit_lamports(&monthly, monthly.expires_at).unwrap();
+        assert_eq!(vault, rent);
+    }
+}
@@ -0,0 +1,301 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+#[instruction(kind: u8)]
+pub struct CreatePredictionPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"prediction", match_state.key().as_ref(), [kind].as_ref()],
+        bump,
+        space = 8 + PredictionPool::SIZE,
+    )]
+    pub pool: Account<'info, PredictionPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(address = match_state.tier)]
+    pub tier: Account<'info, ArenaTier>,
+    #[account(seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+    #[account(
+        mut,
+        seeds = [b"prediction", match_state.key().as_ref(), [pool.kind].as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, PredictionPool>,
+    // The entrant being backed must hold an entry in the match
+    #[account(seeds = [b"entry", match_state.key().as_ref(), entrant_entry.player.as_ref()], bump = entrant_entry.bump)]
+    pub entrant_entry: Account<'info, PlayerEntry>,
+    /// CHECK: the bettor's entry address in this match; must not exist
+    #[account(seeds = [b"entry", match_state.key().as_ref(), bettor.key().as_ref()], bump)]
+    pub bettor_entry: UncheckedAccount<'info>,
+    /// CHECK: the bettor's waitlist address in this match; must not exist
+    #[account(seeds = [b"waitlist", match_state.key().as_ref(), bettor.key().as_ref()], bump)]
+    pub bettor_waitlist: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        seeds = [b"bet_mark", match_state.key().as_ref(), bettor.key().as_ref()],
+        bump,
+        space = 8 + BetMark::SIZE,
+    )]
+    pub bet_mark: Account<'info, BetMark>,
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        seeds = [b"outcome", pool.key().as_ref(), entrant_entry.player.as_ref()],
+        bump,
+        space = 8 + PredictionOutcome::SIZE,
+    )]
+    pub outcome: Account<'info, PredictionOutcome>,
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        seeds = [b"bet", outcome.key().as_ref(), bettor.key().as_ref()],
+        bump,
+        space = 8 + PredictionBet::SIZE,
+    )]
+    pub bet: Account<'info, PredictionBet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolvePrediction<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+    #[account(
+        mut,
+        seeds = [b"prediction", match_state.key().as_ref(), [pool.kind].as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, PredictionPool>,
+    #[account(seeds = [b"alloc", match_state.key().as_ref(), allocation.player.as_ref()], bump = allocation.bump)]
+    pub allocation: Account<'info, WinnerAllocation>,
+    /// CHECK: the allocated player's outcome in this pool; may not exist
+    #[account(mut, seeds = [b"outcome", pool.key().as_ref(), allocation.player.as_ref()], bump)]
+    pub outcome: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"buyback_ura", config.key().as_ref()], bump = config.buyback_ura_bump)]
+    pub buyback_ura_vault: SystemAccount<'info>,
+    #[account(mut, seeds = [b"buyback_urac", config.key().as_ref()], bump = config.buyback_urac_bump)]
+    pub buyback_urac_vault: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPrediction<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"prediction", pool.match_key.as_ref(), [pool.kind].as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, PredictionPool>,
+    #[account(seeds = [b"outcome", pool.key().as_ref(), outcome.entrant.as_ref()], bump = outcome.bump)]
+    pub outcome: Account<'info, PredictionOutcome>,
+    #[account(
+        mut,
+        seeds = [b"bet", outcome.key().as_ref(), bettor.key().as_ref()],
+        bump = bet.bump,
+        has_one = bettor,
+        close = bettor,
+    )]
+    pub bet: Account<'info, PredictionBet>,
+}
+
+#[derive(Accounts)]
+pub struct RefundBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(address = pool.match_key)]
+    pub match_state: Account<'info, MatchState>,
+    #[account(mut, seeds = [b"prediction", pool.match_key.as_ref(), [pool.kind].as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, PredictionPool>,
+    #[account(mut, seeds = [b"outcome", pool.key().as_ref(), outcome.entrant.as_ref()], bump = outcome.bump)]
+    pub outcome: Account<'info, PredictionOutcome>,
+    #[account(
+        mut,
+        seeds = [b"bet", outcome.key().as_ref(), bettor.key().as_ref()],
+        bump = bet.bump,
+        has_one = bettor,
+        has_one = outcome,
+        close = bettor,
+    )]
+    pub bet: Account<'info, PredictionBet>,
+}
+
+// After this, a pool that has not settled refunds its bets instead of resolving.
+fn settle_deadline(day_id: i64) -> Result<i64> {
+    (day_id + 1)
+        .checked_mul(SECONDS_PER_DAY)
+        .and_then(|end| end.checked_add(PREDICTION_SETTLE_WINDOW_SECS))
+        .ok_or(error!(ArenaError::Overflow))
+}
+
+pub(crate) fn create_prediction_pool(ctx: Context<CreatePredictionPool>, kind: u8) -> Result<()> {
+    PredictionKind::from_u8(kind).ok_or(ArenaError::InvalidPredictionKind)?;
+    require!(ctx.accounts.match_state.status == MatchStatus::Open as u8, ArenaError::MatchClosed);
+    let pool = &mut ctx.accounts.pool;
+    pool.match_key = ctx.accounts.match_state.key();
+    pool.kind = kind;
+    pool.bump = ctx.bumps.pool;
+    pool.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+fn check_bet(config: &ArenaConfig, m: &MatchState, amount: u64) -> Result<()> {
+    require_not_paused(config, PAUSE_DEPOSIT)?;
+    require!(amount > 0, ArenaError::InvalidAmount);
+    require!(m.status == MatchStatus::Open as u8, ArenaError::MatchClosed);
+    Ok(())
+}
+
+pub(crate) fn place_bet(ctx: Context<PlaceBet>, amount: u64) -> Result<()> {
+    let m = &ctx.accounts.match_state;
+    check_bet(&ctx.accounts.config, m, amount)?;
+    require_entries_open(&ctx.accounts.tier, m.day_id, Clock::get()?.unix_timestamp)?;
+    require_spectator(&ctx.accounts.bettor_entry, &ctx.accounts.bettor_waitlist)?;
+    let mark = &mut ctx.accounts.bet_mark;
+    if mark.match_key == Pubkey::default() {
+        mark.match_key = m.key();
+        mark.bettor = ctx.accounts.bettor.key();
+        mark.bump = ctx.bumps.bet_mark;
+        mark.version = ACCOUNT_VERSION;
+    }
+
+    let pool = &mut ctx.accounts.pool;
+    pool.total_staked = pool.total_staked.checked_add(amount).ok_or(ArenaError::Overflow)?;
+    let outcome = &mut ctx.accounts.outcome;
+    if outcome.pool == Pubkey::default() {
+        outcome.pool = pool.key();
+        outcome.entrant = ctx.accounts.entrant_entry.player;
+        outcome.bump = ctx.bumps.outcome;
+        outcome.version = ACCOUNT_VERSION;
+    }
+    outcome.total_staked = outcome.total_staked.checked_add(amount).ok_or(ArenaError::Overflow)?;
+    let bet = &mut ctx.accounts.bet;
+    if bet.outcome == Pubkey::default() {
+        bet.outcome = outcome.key();
+        bet.bettor = ctx.accounts.bettor.key();
+        bet.bump = ctx.bumps.bet;
+        bet.version = ACCOUNT_VERSION;
+    }
+    bet.amount = bet.amount.checked_add(amount).ok_or(ArenaError::Overflow)?;
+
+    let ix = system_instruction::transfer(&ctx.accounts.bettor.key(), &pool.key(), amount);
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.bettor.to_account_info(),
+            pool.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        &[],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn resolve_prediction(ctx: Context<ResolvePrediction>) -> Result<()> {
+    // Records a result and, on the last rank, pays the fee out of the pool
+    require_not_paused(&ctx.accounts.config, PAUSE_ALLOCATE | PAUSE_CLAIM)?;
+    let m = &ctx.accounts.match_state;
+    require!(m.status == MatchStatus::Finalized as u8, ArenaError::MatchNotFinalized);
+    require!(Clock::get()?.unix_timestamp < settle_deadline(m.day_id)?, ArenaError::PredictionExpired);
+    let pool = &mut ctx.accounts.pool;
+    require!(!pool.settled, ArenaError::PredictionSettled);
+    let kind = PredictionKind::from_u8(pool.kind).ok_or(ArenaError::InvalidPredictionKind)?;
+    let winning_ranks = kind.winning_ranks(m.winners_count);
+    let rank = ctx.accounts.allocation.rank;
+    require!(rank >= 1 && rank <= winning_ranks, ArenaError::InvalidRank);
+    let bit = 1u8 << (rank - 1);
+    require!(pool.resolved_ranks & bit == 0, ArenaError::PredictionSettled);
+    pool.resolved_ranks |= bit;
+
+    // The outcome address is fixed by seeds; an empty account means nobody bet on them
+    let outcome_info = &ctx.accounts.outcome;
+    if !outcome_info.data_is_empty() {
+        let mut data = outcome_info.try_borrow_mut_data()?;
+        let mut outcome = PredictionOutcome::try_deserialize(&mut &data[..])?;
+        outcome.won = true;
+        pool.winning_total = pool.winning_total.checked_add(outcome.total_staked).ok_or(ArenaError::Overflow)?;
+        outcome.try_serialize(&mut &mut data[..])?;
+    }
+
+    if pool.resolved_ranks.count_ones() == winning_ranks {
+        pool.settled = true;
+        // Nobody picked a winner: no fee, every bet is refunded
+        if pool.winning_total > 0 {
+            let fee = (pool.total_staked as u128 * ctx.accounts.config.prediction_fee_bps as u128 / BPS_100) as u64;
+            let ura = fee / 2;
+            pool.fee = fee;
+            let info = pool.to_account_info();
+            move_lamports(&info, &ctx.accounts.buyback_ura_vault.to_account_info(), ura)?;
+            move_lamports(&info, &ctx.accounts.buyback_urac_vault.to_account_info(), fee - ura)?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn claim_prediction(ctx: Context<ClaimPrediction>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let pool = &ctx.accounts.pool;
+    require!(pool.settled, ArenaError::PredictionPending);
+    let bet = &ctx.accounts.bet;
+    let payout = if pool.winning_total == 0 {
+        bet.amount
+    } else {
+        require!(ctx.accounts.outcome.won, ArenaError::ZeroAllocation);
+        pari_mutuel_payout(pool.total_staked - pool.fee, bet.amount, pool.winning_total)
+    };
+    move_lamports(&pool.to_account_info(), &ctx.accounts.bettor.to_account_info(), payout)
+}
+
+pub(crate) fn refund_bet(ctx: Context<RefundBet>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let pool = &mut ctx.accounts.pool;
+    require!(!pool.settled, ArenaError::PredictionSettled);
+    let deadline = settle_deadline(ctx.accounts.match_state.day_id)?;
+    require!(Clock::get()?.unix_timestamp >= deadline, ArenaError::PredictionNotExpired);
+
+    let amount = ctx.accounts.bet.amount;
+    pool.total_staked = pool.total_staked.checked_sub(amount).ok_or(ArenaError::Overflow)?;
+    let outcome = &mut ctx.accounts.outcome;
+    outcome.total_staked = outcome.total_staked.checked_sub(amount).ok_or(ArenaError::Overflow)?;
+    move_lamports(&pool.to_account_info(), &ctx.accounts.bettor.to_account_info(), amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bets_stop_while_paused() {
+        let mut config = ArenaConfig::default();
+        let open = MatchState { status: MatchStatus::Open as u8, ..Default::default() };
+        assert!(check_bet(&config, &open, 10).is_ok());
+        assert!(check_bet(&config, &open, 0).is_err());
+        config.paused_flags = PAUSE_DEPOSIT;
+        assert!(check_bet(&config, &open, 10).is_err());
+    }
+}
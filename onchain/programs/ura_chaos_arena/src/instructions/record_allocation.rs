@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct RecordAllocation<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+    /// CHECK: winner pubkey recorded in allocation
+    pub winner: UncheckedAccount<'info>,
+    // The winner must hold an entry in this match; its weight feeds stake-weighted payouts
+    #[account(seeds = [b"entry", match_state.key().as_ref(), winner.key().as_ref()], bump = entry.bump)]
+    pub entry: Account<'info, PlayerEntry>,
+    // Created here for winners whose entry predates profiles
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"profile", winner.key().as_ref()],
+        bump,
+        space = 8 + PlayerProfile::SIZE,
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    // Required when the winner entered with a guild
+    #[account(
+        mut,
+        seeds = [b"standing", guild_standing.tier.as_ref(), &guild_standing.day_id.to_le_bytes(), guild_standing.guild.as_ref()],
+        bump = guild_standing.bump,
+    )]
+    pub guild_standing: Option<Account<'info, GuildStanding>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"alloc", match_state.key().as_ref(), winner.key().as_ref()],
+        bump,
+        space = 8 + WinnerAllocation::SIZE,
+    )]
+    pub allocation: Account<'info, WinnerAllocation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn record_allocation(ctx: Context<RecordAllocation>, rank: u32) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_ALLOCATE)?;
+    let m = &mut ctx.accounts.match_state;
+    require!(m.status == MatchStatus::Finalized as u8, ArenaError::MatchNotFinalized);
+
+    let alloc = &mut ctx.accounts.allocation;
+    alloc.match_key = m.key();
+    alloc.player = ctx.accounts.winner.key();
+    alloc.rank = rank;
+    alloc.weight = ctx.accounts.entry.weight;
+    alloc.claimed = false;
+    alloc.bump = ctx.bumps.allocation;
+    alloc.version = ACCOUNT_VERSION;
+    alloc.amount = add_allocation(m, rank, alloc.weight)?;
+
+    // Team arenas: the winner's rank scores for the guild they entered with
+    let guild = ctx.accounts.entry.guild;
+    if guild != Pubkey::default() {
+        let st = ctx.accounts.guild_standing.as_mut().ok_or(ArenaError::GuildStandingsIncomplete)?;
+        require!(st.guild == guild && st.tier == m.tier && st.day_id == m.day_id, ArenaError::GuildStandingsIncomplete);
+        st.score = st.score.checked_add((m.winners_count + 1 - rank) as u64).ok_or(ArenaError::Overflow)?;
+    }
+
+    let profile_bump = ctx.bumps.profile;
+    record_prize(&mut ctx.accounts.profile, ctx.accounts.winner.key(), profile_bump, m.day_id, rank)?;
+
+    Ok(())
+}
+
+// Counts one winner against the match and returns its fixed amount (0 when stake-weighted).
+// Ranks are recorded in order, 1 to winners_count, so each rank is paid once and a late extra
+// score cannot shrink the denominator claims already used.
+fn add_allocation(m: &mut MatchState, rank: u32, weight: u32) -> Result<u64> {
+    require!(rank <= m.winners_count && rank == m.allocations_recorded + 1, ArenaError::InvalidRank);
+    let amount = if m.stake_weight_cap == 0 {
+        let amount = compute_rank_allocation(m.prize_pool, m.winners_count, m.group2_count, m.group3_count, rank, m.remainder).map_err(ArenaError::from)?;
+        require!(amount > 0, ArenaError::ZeroAllocation);
+        amount
+    } else {
+        // Stake-weighted: the amount is fixed at claim time, once every winner's score is in.
+        // Weight comes from the on-chain entry, so the referee only controls the rank.
+        let share = rank_share_ppt(m.winners_count, m.group2_count, m.group3_count, rank).map_err(ArenaError::from)?;
+        let score = stake_score(share, weight).map_err(ArenaError::from)?;
+        m.total_stake_score = m.total_stake_score.checked_add(score).ok_or(ArenaError::Overflow)?;
+        0
+    };
+    m.allocations_recorded += 1;
+    Ok(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocations_capped_at_winners() {
+        let (winners_count, group2_count, group3_count) = winner_groups(20);
+        let mut m = MatchState {
+            winners_count,
+            group2_count,
+            group3_count,
+            prize_pool: 1_000_000_000,
+            stake_weight_cap: 5,
+            ..MatchState::default()
+        };
+        let weight = |rank: u32| WEIGHT_ONE as u32 * (1 + rank % 5);
+        for rank in 1..=winners_count {
+            add_allocation(&mut m, rank, weight(rank)).unwrap();
+        }
+        // One more winner, even at a valid rank, would dilute claims already paid
+        assert!(add_allocation(&mut m, 1, weight(1)).is_err());
+        assert!(add_allocation(&mut m, winners_count + 1, weight(1)).is_err());
+        assert_eq!(m.allocations_recorded, winners_count);
+
+        let mut paid = 0u64;
+        for rank in 1..=winners_count {
+            let share = rank_share_ppt(winners_count, group2_count, group3_count, rank).unwrap();
+            let score = stake_score(share, weight(rank)).unwrap();
+            paid += stake_weighted_allocation(m.prize_pool, score, m.total_stake_score).unwrap();
+        }
+        assert!(paid <= m.prize_pool);
+
+        // Rank-only matches are capped the same way, and a rank already paid cannot fill
+        // another winner's slot
+        m.stake_weight_cap = 0;
+        m.allocations_recorded = 0;
+        assert!(add_allocation(&mut m, 2, WEIGHT_ONE as u32).is_err());
+        let mut paid = add_allocation(&mut m, 1, WEIGHT_ONE as u32).unwrap();
+        assert!(add_allocation(&mut m, 1, WEIGHT_ONE as u32).is_err());
+        for rank in 2..=winners_count {
+            paid += add_allocation(&mut m, rank, WEIGHT_ONE as u32).unwrap();
+        }
+        assert!(add_allocation(&mut m, winners_count, WEIGHT_ONE as u32).is_err());
+        assert!(paid <= m.prize_pool);
+    }
+
+    #[test]
+    fn allocations_recorded_in_rank_order() {
+        let (winners_count, group2_count, group3_count) = winner_groups(20);
+        for stake_weight_cap in [0, 5] {
+            let mut m = MatchState {
+                winners_count,
+                group2_count,
+                group3_count,
+                prize_pool: 1_000_000_000,
+                stake_weight_cap,
+                ..MatchState::default()
+            };
+            // Ranks must come 1, 2, 3, ...; a skipped or repeated rank is rejected and
+            // leaves the match as it was
+            assert!(add_allocation(&mut m, 2, WEIGHT_ONE as u32).is_err());
+            add_allocation(&mut m, 1, WEIGHT_ONE as u32).unwrap();
+            assert!(add_allocation(&mut m, 3, WEIGHT_ONE as u32).is_err());
+            assert!(add_allocation(&mut m, 1, WEIGHT_ONE as u32).is_err());
+            assert_eq!(m.allocations_recorded, 1);
+            add_allocation(&mut m, 2, WEIGHT_ONE as u32).unwrap();
+            assert_eq!(m.allocations_recorded, 2);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::PAUSE_BURN;
+use crate::state::{ArenaConfig, Stats};
+use crate::utils::require_not_paused;
+
+// Authority posts buy+burn results to stats
+#[derive(Accounts)]
+pub struct RecordBurned<'info> {
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"stats", config.key().as_ref()], bump = stats.bump)]
+    pub stats: Account<'info, Stats>,
+}
+
+pub(crate) fn record_burned(
+    ctx: Context<RecordBurned>,
+    ura_burned_atoms: u128,
+    urac_burned_atoms: u128,
+    ura_sol_spent: u64,
+    urac_sol_spent: u64,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_BURN)?;
+    let stats = &mut ctx.accounts.stats;
+    stats.total_ura_burned_atoms = stats.total_ura_burned_atoms.saturating_add(ura_burned_atoms);
+    stats.total_urac_burned_atoms = stats.total_urac_burned_atoms.saturating_add(urac_burned_atoms);
+    stats.total_ura_burn_sol = stats.total_ura_burn_sol.saturating_add(ura_sol_spent);
+    stats.total_urac_burn_sol = stats.total_urac_burn_sol.saturating_add(urac_sol_spent);
+    Ok(())
+}
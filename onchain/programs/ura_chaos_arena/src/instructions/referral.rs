@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct CreateReferral<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    #[account(
+        init,
+        payer = referrer,
+        seeds = [b"referral", referrer.key().as_ref()],
+        bump,
+        space = 8 + ReferralAccount::SIZE,
+    )]
+    pub referral: Account<'info, ReferralAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferral<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"referral", referrer.key().as_ref()], bump = referral.bump, has_one = referrer)]
+    pub referral: Account<'info, ReferralAccount>,
+}
+
+pub(crate) fn create_referral(ctx: Context<CreateReferral>) -> Result<()> {
+    let r = &mut ctx.accounts.referral;
+    r.referrer = ctx.accounts.referrer.key();
+    r.bump = ctx.bumps.referral;
+    r.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let r = &mut ctx.accounts.referral;
+    let amount = r.accrued_lamports;
+    require!(amount > 0, ArenaError::ZeroAllocation);
+    r.accrued_lamports = 0;
+    move_lamports(&r.to_account_info(), &ctx.accounts.referrer.to_account_info(), amount)
+}
@@ -0,0 +1,229 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct InitStaking<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    pub urac_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"staking", config.key().as_ref()],
+        bump,
+        space = 8 + StakingPool::SIZE,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"stake_vault", config.key().as_ref()],
+        bump,
+        token::mint = urac_mint,
+        token::authority = staking_pool,
+        token::token_program = token_program,
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        mut,
+        seeds = [b"staking", config.key().as_ref()],
+        bump = staking_pool.bump,
+        has_one = urac_mint,
+        has_one = stake_vault,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    pub urac_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = urac_mint, token::authority = owner)]
+    pub owner_tokens: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [b"stake", owner.key().as_ref()],
+        bump,
+        space = 8 + StakePosition::SIZE,
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    pub owner: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"staking", config.key().as_ref()], bump = staking_pool.bump)]
+    pub staking_pool: Account<'info, StakingPool>,
+    #[account(mut, seeds = [b"stake", owner.key().as_ref()], bump = position.bump, has_one = owner)]
+    pub position: Account<'info, StakePosition>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnstaked<'info> {
+    pub owner: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        seeds = [b"staking", config.key().as_ref()],
+        bump = staking_pool.bump,
+        has_one = urac_mint,
+        has_one = stake_vault,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    pub urac_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = urac_mint)]
+    pub owner_tokens: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, seeds = [b"stake", owner.key().as_ref()], bump = position.bump, has_one = owner)]
+    pub position: Account<'info, StakePosition>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakingRewards<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"staking", config.key().as_ref()], bump = staking_pool.bump)]
+    pub staking_pool: Account<'info, StakingPool>,
+    #[account(mut, seeds = [b"stake", owner.key().as_ref()], bump = position.bump, has_one = owner)]
+    pub position: Account<'info, StakePosition>,
+}
+
+pub(crate) fn init_staking(ctx: Context<InitStaking>, revenue_bps: u16, cooldown_secs: u32) -> Result<()> {
+    require!(revenue_bps as u128 <= BPS_100, ArenaError::InvalidStakingParams);
+    require!(cooldown_secs <= UNSTAKE_COOLDOWN_MAX_SECS, ArenaError::InvalidStakingParams);
+    let pool = &mut ctx.accounts.staking_pool;
+    pool.urac_mint = ctx.accounts.urac_mint.key();
+    pool.stake_vault = ctx.accounts.stake_vault.key();
+    pool.revenue_bps = revenue_bps;
+    pool.cooldown_secs = cooldown_secs;
+    pool.bump = ctx.bumps.staking_pool;
+    pool.version = ACCOUNT_VERSION;
+    ctx.accounts.config.staking_enabled = true;
+    Ok(())
+}
+
+fn check_stake(config: &ArenaConfig, amount: u64) -> Result<()> {
+    require_not_paused(config, PAUSE_DEPOSIT)?;
+    require!(amount > 0, ArenaError::InvalidAmount);
+    Ok(())
+}
+
+pub(crate) fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    check_stake(&ctx.accounts.config, amount)?;
+    let pool = &mut ctx.accounts.staking_pool;
+    let pos = &mut ctx.accounts.position;
+    if pos.owner == Pubkey::default() {
+        pos.owner = ctx.accounts.owner.key();
+        pos.bump = ctx.bumps.position;
+        pos.version = ACCOUNT_VERSION;
+    }
+    settle_stake_rewards(pos, pool.acc_reward_per_share)?;
+    pos.amount = pos.amount.checked_add(amount).ok_or(ArenaError::Overflow)?;
+    pos.reward_debt = reward_debt(pos.amount, pool.acc_reward_per_share).map_err(ArenaError::from)?;
+    pool.total_staked = pool.total_staked.checked_add(amount).ok_or(ArenaError::Overflow)?;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.owner_tokens.to_account_info(),
+                mint: ctx.accounts.urac_mint.to_account_info(),
+                to: ctx.accounts.stake_vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.urac_mint.decimals,
+    )
+}
+
+pub(crate) fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.staking_pool;
+    let pos = &mut ctx.accounts.position;
+    require!(amount > 0 && amount <= pos.amount, ArenaError::InvalidAmount);
+    settle_stake_rewards(pos, pool.acc_reward_per_share)?;
+    pos.amount -= amount;
+    pos.reward_debt = reward_debt(pos.amount, pool.acc_reward_per_share).map_err(ArenaError::from)?;
+    pos.unstaking_amount = pos.unstaking_amount.checked_add(amount).ok_or(ArenaError::Overflow)?;
+    pos.unstake_available_at = Clock::get()?.unix_timestamp + pool.cooldown_secs as i64;
+    pool.total_staked -= amount;
+    Ok(())
+}
+
+pub(crate) fn withdraw_unstaked(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let pos = &mut ctx.accounts.position;
+    let amount = pos.unstaking_amount;
+    require!(amount > 0, ArenaError::InvalidAmount);
+    require!(Clock::get()?.unix_timestamp >= pos.unstake_available_at, ArenaError::UnstakeCooldown);
+    pos.unstaking_amount = 0;
+
+    let config_key = ctx.accounts.config.key();
+    let pool_seeds: &[&[u8]] = &[b"staking", config_key.as_ref(), &[ctx.accounts.staking_pool.bump]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                mint: ctx.accounts.urac_mint.to_account_info(),
+                to: ctx.accounts.owner_tokens.to_account_info(),
+                authority: ctx.accounts.staking_pool.to_account_info(),
+            },
+            &[pool_seeds],
+        ),
+        amount,
+        ctx.accounts.urac_mint.decimals,
+    )
+}
+
+pub(crate) fn claim_staking_rewards(ctx: Context<ClaimStakingRewards>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let pool = &ctx.accounts.staking_pool;
+    let pos = &mut ctx.accounts.position;
+    settle_stake_rewards(pos, pool.acc_reward_per_share)?;
+    let amount = pos.pending_rewards;
+    require!(amount > 0, ArenaError::ZeroAllocation);
+    pos.pending_rewards = 0;
+    move_lamports(&pool.to_account_info(), &ctx.accounts.owner.to_account_info(), amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stake_stops_while_paused() {
+        let mut config = ArenaConfig::default();
+        assert!(check_stake(&config, 10).is_ok());
+        assert!(check_stake(&config, 0).is_err());
+        config.paused_flags = PAUSE_DEPOSIT;
+        assert!(check_stake(&config, 10).is_err());
+    }
+}
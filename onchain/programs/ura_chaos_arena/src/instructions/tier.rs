@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(tier_id: u8)]
+pub struct CreateTier<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"tier", [tier_id].as_ref()],
+        bump,
+        space = 8 + ArenaTier::SIZE,
+    )]
+    pub tier: Account<'info, ArenaTier>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTier<'info> {
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"tier", [tier.tier_id].as_ref()], bump = tier.bump)]
+    pub tier: Account<'info, ArenaTier>,
+}
+
+pub(crate) fn create_tier(ctx: Context<CreateTier>, tier_id: u8, ticket_price_usd_micros: u64) -> Result<()> {
+    require!(
+        ticket_price_usd_micros > 0 && ticket_price_usd_micros <= TICKET_USD_MICROS_MAX,
+        ArenaError::InvalidTicketPrice
+    );
+    let tier = &mut ctx.accounts.tier;
+    tier.tier_id = tier_id;
+    tier.ticket_price_usd_micros = ticket_price_usd_micros;
+    tier.bump = ctx.bumps.tier;
+    tier.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn update_tier(ctx: Context<UpdateTier>, ticket_price_usd_micros: u64) -> Result<()> {
+    require!(
+        ticket_price_usd_micros > 0 && ticket_price_usd_micros <= TICKET_USD_MICROS_MAX,
+        ArenaError::InvalidTicketPrice
+    );
+    ctx.accounts.tier.ticket_price_usd_micros = ticket_price_usd_micros;
+    Ok(())
+}
+
+pub(crate) fn set_player_cap(ctx: Context<UpdateTier>, max_players: u32, waitlist_enabled: bool) -> Result<()> {
+    let tier = &mut ctx.accounts.tier;
+    tier.max_players = max_players;
+    tier.waitlist_enabled = waitlist_enabled;
+    Ok(())
+}
+
+pub(crate) fn set_entry_window(ctx: Context<UpdateTier>, join_cutoff_secs: u32, entry_window_secs: u32) -> Result<()> {
+    require!((join_cutoff_secs as i64) < SECONDS_PER_DAY, ArenaError::InvalidEntryWindow);
+    require!(entry_window_secs <= join_cutoff_secs, ArenaError::InvalidEntryWindow);
+    let tier = &mut ctx.accounts.tier;
+    tier.join_cutoff_secs = join_cutoff_secs;
+    tier.entry_window_secs = entry_window_secs;
+    Ok(())
+}
+
+pub(crate) fn set_pass_discount(ctx: Context<UpdateTier>, discount_bps: u16) -> Result<()> {
+    require!(discount_bps <= PASS_DISCOUNT_BPS_MAX, ArenaError::InvalidPassDiscount);
+    ctx.accounts.tier.pass_discount_bps = discount_bps;
+    Ok(())
+}
+
+pub(crate) fn set_max_days_ahead(ctx: Context<UpdateTier>, max_days_ahead: u8) -> Result<()> {
+    require!(max_days_ahead <= MAX_DAYS_AHEAD, ArenaError::InvalidFutureDay);
+    ctx.accounts.tier.max_days_ahead = max_days_ahead;
+    Ok(())
+}
+
+pub(crate) fn set_reentry(ctx: Context<UpdateTier>, allow: bool) -> Result<()> {
+    ctx.accounts.tier.allow_reentry = allow;
+    Ok(())
+}
+
+pub(crate) fn set_token_gate(ctx: Context<UpdateTier>, kind: u8, mint: Pubkey, min_amount: u64) -> Result<()> {
+    let gate = TokenGate::from_u8(kind).ok_or(ArenaError::InvalidTokenGate)?;
+    if gate == TokenGate::TokenBalance {
+        require!(min_amount > 0, ArenaError::InvalidTokenGate);
+    }
+    if gate == TokenGate::VerifiedCollection {
+        require!(mint != Pubkey::default(), ArenaError::InvalidTokenGate);
+    }
+    let tier = &mut ctx.accounts.tier;
+    tier.gate_kind = kind;
+    tier.gate_mint = if gate == TokenGate::None { Pubkey::default() } else { mint };
+    tier.gate_min_amount = if gate == TokenGate::TokenBalance { min_amount } else { 0 };
+    Ok(())
+}
+
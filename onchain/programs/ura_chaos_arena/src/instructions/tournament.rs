@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+#[instruction(tournament_id: u64)]
+pub struct CreateTournament<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    // Per organizer, so tournaments cannot exhaust a shared id space
+    #[account(
+        init,
+        payer = organizer,
+        seeds = [b"tier", organizer.key().as_ref(), tournament_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + ArenaTier::SIZE,
+    )]
+    pub tier: Account<'info, ArenaTier>,
+    #[account(
+        init,
+        payer = organizer,
+        seeds = [b"tournament", tier.key().as_ref()],
+        bump,
+        space = 8 + Tournament::SIZE,
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTournament<'info> {
+    pub organizer: Signer<'info>,
+    // Pinned by the tournament's seeds and has_one
+    #[account(mut)]
+    pub tier: Account<'info, ArenaTier>,
+    #[account(mut, seeds = [b"tournament", tier.key().as_ref()], bump = tournament.bump, has_one = organizer, has_one = tier)]
+    pub tournament: Account<'info, Tournament>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterTournament<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(seeds = [b"tournament", tournament.tier.as_ref()], bump = tournament.bump)]
+    pub tournament: Account<'info, Tournament>,
+    #[account(
+        init,
+        payer = player,
+        seeds = [b"invite", tournament.key().as_ref(), player.key().as_ref()],
+        bump,
+        space = 8 + TournamentInvite::SIZE,
+    )]
+    pub invite: Account<'info, TournamentInvite>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTournamentRevenue<'info> {
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(mut, seeds = [b"tournament", tournament.tier.as_ref()], bump = tournament.bump, has_one = organizer)]
+    pub tournament: Account<'info, Tournament>,
+}
+
+pub(crate) fn create_tournament(
+    ctx: Context<CreateTournament>,
+    _tournament_id: u64,
+    entry_fee_lamports: u64,
+    merkle_root: [u8; 32],
+) -> Result<()> {
+    require!(entry_fee_lamports > 0, ArenaError::InvalidAmount);
+    let tier = &mut ctx.accounts.tier;
+    tier.bump = ctx.bumps.tier;
+    tier.version = ACCOUNT_VERSION;
+    tier.tournament = true;
+    tier.entry_fee_lamports = entry_fee_lamports;
+
+    let t = &mut ctx.accounts.tournament;
+    t.organizer = ctx.accounts.organizer.key();
+    t.tier = tier.key();
+    t.merkle_root = merkle_root;
+    t.bump = ctx.bumps.tournament;
+    t.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn update_tournament(ctx: Context<UpdateTournament>, entry_fee_lamports: u64, merkle_root: [u8; 32]) -> Result<()> {
+    require!(entry_fee_lamports > 0, ArenaError::InvalidAmount);
+    // Matches already open keep their fee (MatchState::entry_fee_lamports)
+    ctx.accounts.tier.entry_fee_lamports = entry_fee_lamports;
+    ctx.accounts.tournament.merkle_root = merkle_root;
+    Ok(())
+}
+
+pub(crate) fn register_tournament(ctx: Context<RegisterTournament>, proof: Vec<[u8; 32]>) -> Result<()> {
+    let player = ctx.accounts.player.key();
+    require!(
+        verify_allowlist_proof(&proof, &ctx.accounts.tournament.merkle_root, &player),
+        ArenaError::NotInvited
+    );
+    let invite = &mut ctx.accounts.invite;
+    invite.tournament = ctx.accounts.tournament.key();
+    invite.player = player;
+    invite.bump = ctx.bumps.invite;
+    invite.version = ACCOUNT_VERSION;
+    Ok(())
+}
+
+pub(crate) fn claim_tournament_revenue(ctx: Context<ClaimTournamentRevenue>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let t = &mut ctx.accounts.tournament;
+    let amount = t.accrued_lamports;
+    require!(amount > 0, ArenaError::ZeroAllocation);
+    t.accrued_lamports = 0;
+    move_lamports(&t.to_account_info(), &ctx.accounts.organizer.to_account_info(), amount)
+}
@@ -0,0 +1,278 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+use anchor_lang::AccountsClose;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+#[instruction(day_id: i64)]
+pub struct JoinWaitlist<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    // Any tier, see Join::tier
+    pub tier: Account<'info, ArenaTier>,
+    #[account(
+        mut,
+        seeds = [b"match", tier.key().as_ref(), day_id.to_le_bytes().as_ref()],
+        bump = match_state.bump,
+    )]
+    pub match_state: Account<'info, MatchState>,
+    /// CHECK: the player's entry PDA; must not exist yet
+    #[account(seeds = [b"entry", match_state.key().as_ref(), player.key().as_ref()], bump)]
+    pub entry: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = player,
+        seeds = [b"waitlist", match_state.key().as_ref(), player.key().as_ref()],
+        bump,
+        space = 8 + WaitlistEntry::SIZE,
+    )]
+    pub waitlist: Account<'info, WaitlistEntry>,
+    /// CHECK: see Join::price_update
+    #[account(owner = pyth_receiver::ID)]
+    pub price_update: Option<UncheckedAccount<'info>>,
+    // Gate and invite accounts as in Join
+    pub gate_tokens: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: see Join::gate_metadata
+    pub gate_metadata: Option<UncheckedAccount<'info>>,
+    pub gate_nft_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(seeds = [b"invite", invite.tournament.as_ref(), player.key().as_ref()], bump = invite.bump)]
+    pub invite: Option<Account<'info, TournamentInvite>>,
+    /// CHECK: see Join::bet_mark
+    #[account(seeds = [b"bet_mark", match_state.key().as_ref(), player.key().as_ref()], bump)]
+    pub bet_mark: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdmitFromWaitlist<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(address = match_state.tier)]
+    pub tier: Account<'info, ArenaTier>,
+    #[account(mut, seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+    #[account(mut, seeds = [b"vault", match_state.key().as_ref()], bump)]
+    pub match_vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"waitlist", match_state.key().as_ref(), player.key().as_ref()],
+        bump = waitlist.bump,
+        has_one = player,
+        close = player,
+    )]
+    pub waitlist: Account<'info, WaitlistEntry>,
+    /// CHECK: waitlisted wallet, receives the waitlist account's rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+    // init_if_needed: a head that already holds an entry is skipped rather than failing forever
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"entry", match_state.key().as_ref(), player.key().as_ref()],
+        bump,
+        space = 8 + PlayerEntry::SIZE,
+    )]
+    pub entry: Account<'info, PlayerEntry>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+        space = 8 + PlayerProfile::SIZE,
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    // Required when the player's profile has a referrer: the rebate is paid as on any join
+    #[account(mut, seeds = [b"referral", referral.referrer.as_ref()], bump = referral.bump)]
+    pub referral: Option<Account<'info, ReferralAccount>>,
+    // Team arenas: the player's GuildMember address and their guild's standing for this match.
+    // Either may be empty; a head that cannot enter the team arena is refunded and skipped.
+    /// CHECK: address checked by seeds, contents in admission_guild
+    #[account(seeds = [b"member", player.key().as_ref()], bump)]
+    pub guild_member: Option<UncheckedAccount<'info>>,
+    /// CHECK: address and contents checked in admission_guild
+    #[account(mut)]
+    pub guild_standing: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundWaitlist<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ArenaConfig>,
+    #[account(seeds = [b"match", match_state.tier.as_ref(), &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
+    pub match_state: Account<'info, MatchState>,
+    #[account(
+        mut,
+        seeds = [b"waitlist", match_state.key().as_ref(), player.key().as_ref()],
+        bump = waitlist.bump,
+        has_one = player,
+        close = player,
+    )]
+    pub waitlist: Account<'info, WaitlistEntry>,
+    /// CHECK: waitlisted wallet, receives escrow and rent
+    #[account(mut)]
+    pub player: UncheckedAccount<'info>,
+}
+
+pub(crate) fn join_waitlist(ctx: Context<JoinWaitlist>, day_id: i64, amount: u64) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_JOIN)?;
+    let config = &ctx.accounts.config;
+    let tier = &ctx.accounts.tier;
+    require!(tier.waitlist_enabled, ArenaError::WaitlistDisabled);
+    let clock = Clock::get()?;
+    let min_lamports = ticket_lamports(config, tier, Some(&ctx.accounts.match_state), ctx.accounts.price_update.as_ref(), clock.unix_timestamp)?;
+    let ticket = min_lamports.max(config.min_ticket_lamports);
+    require!(amount > 0 && amount >= ticket, ArenaError::TicketTooCheap);
+
+    let at = entry_time(config, tier, day_id, clock.unix_timestamp)?;
+    let m = &mut ctx.accounts.match_state;
+    require_entries_open(tier, m.day_id, at)?;
+    require!(m.status == MatchStatus::Open as u8, ArenaError::MatchClosed);
+    require!(tier.max_players > 0 && m.player_count >= tier.max_players, ArenaError::MatchNotFull);
+    require!(ctx.accounts.entry.data_is_empty(), ArenaError::AlreadyJoined);
+    require_no_bets(&ctx.accounts.bet_mark)?;
+    require_token_gate(
+        tier,
+        &ctx.accounts.player.key(),
+        ctx.accounts.gate_tokens.as_ref(),
+        ctx.accounts.gate_metadata.as_ref(),
+        ctx.accounts.gate_nft_mint.as_ref(),
+    )?;
+    require_invited(tier, &ctx.accounts.player.key(), ctx.accounts.invite.as_ref())?;
+
+    let w = &mut ctx.accounts.waitlist;
+    w.match_key = m.key();
+    w.player = ctx.accounts.player.key();
+    w.escrow = amount;
+    w.ticket = ticket;
+    w.queued_at = clock.unix_timestamp;
+    w.position = m.waitlist_count;
+    w.bump = ctx.bumps.waitlist;
+    w.version = ACCOUNT_VERSION;
+    m.waitlist_count = m.waitlist_count.checked_add(1).ok_or(ArenaError::Overflow)?;
+
+    // Escrow sits on top of the waitlist account's rent
+    let ix = system_instruction::transfer(&ctx.accounts.player.key(), &ctx.accounts.waitlist.key(), amount);
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.player.to_account_info(),
+            ctx.accounts.waitlist.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        &[],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn admit_from_waitlist(ctx: Context<AdmitFromWaitlist>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_JOIN)?;
+    let now = Clock::get()?.unix_timestamp;
+    let tier = &ctx.accounts.tier;
+    let m = &mut ctx.accounts.match_state;
+    require_admission_open(&ctx.accounts.config, tier, m.day_id, now)?;
+    require!(m.status == MatchStatus::Open as u8, ArenaError::MatchClosed);
+    let w = &ctx.accounts.waitlist;
+    require!(w.position == m.waitlist_head, ArenaError::WaitlistOutOfOrder);
+    m.waitlist_head = m.waitlist_head.checked_add(1).ok_or(ArenaError::Overflow)?;
+    // A head that already plays would stall the queue; `close = player` refunds its escrow
+    if ctx.accounts.entry.tickets > 0 {
+        return Ok(());
+    }
+    require!(tier.max_players == 0 || m.player_count < tier.max_players, ArenaError::MatchFull);
+    let mut guild = None;
+    if m.guild_prize_bps > 0 {
+        guild = admission_guild(m, ctx.accounts.guild_member.as_ref(), ctx.accounts.guild_standing.as_ref())?;
+        if guild.is_none() {
+            // Nothing was written to the new entry; hand its rent back to the payer
+            return ctx.accounts.entry.close(ctx.accounts.payer.to_account_info());
+        }
+    }
+
+    let escrow = w.escrow;
+    let entry = &mut ctx.accounts.entry;
+    entry.match_key = m.key();
+    entry.player = w.player;
+    entry.joined_at = now;
+    entry.bump = ctx.bumps.entry;
+    entry.version = ACCOUNT_VERSION;
+    entry.weight = stake_weight(escrow, w.ticket, m.stake_weight_cap).map_err(ArenaError::from)?;
+    entry.tickets = 1;
+    entry.paid = escrow;
+    let profile_bump = ctx.bumps.profile;
+    record_match_played(&mut ctx.accounts.profile, w.player, profile_bump, m.day_id, m.day_id)?;
+    if let Some((key, mut st)) = guild {
+        enter_guild(m, entry, key, &mut st)?;
+        let info = ctx.accounts.guild_standing.as_ref().ok_or(ArenaError::GuildRequired)?;
+        st.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+
+    // Referred players' rebates skip the vault, as in enter_match
+    let waitlist = ctx.accounts.waitlist.to_account_info();
+    let mut rebate = 0;
+    let referrer = ctx.accounts.profile.referrer;
+    if referrer != Pubkey::default() {
+        let r = ctx.accounts.referral.as_mut().ok_or(ArenaError::MissingReferral)?;
+        require_keys_eq!(r.referrer, referrer, ArenaError::MissingReferral);
+        rebate = referral_rebate(escrow, ctx.accounts.config.referral_bps);
+        if rebate > 0 {
+            move_lamports(&waitlist, &r.to_account_info(), rebate)?;
+            credit_referral(r, m, rebate)?;
+        }
+    }
+    move_lamports(&waitlist, &ctx.accounts.match_vault.to_account_info(), escrow - rebate)?;
+
+    m.player_count = m.player_count.checked_add(1).ok_or(ArenaError::Overflow)?;
+    m.ticket_count = m.ticket_count.checked_add(1).ok_or(ArenaError::Overflow)?;
+    m.pot_lamports = m.pot_lamports.checked_add(escrow).ok_or(ArenaError::Overflow)?;
+    Ok(())
+}
+
+// Team arenas: the head's guild and its standing for `m`, or None when the head verifiably
+// cannot enter (no guild, no standing opened for this match, or a new guild past
+// MAX_GUILDS_PER_MATCH). Missing accounts fail instead, so a crank cannot skip a head at will.
+fn admission_guild(
+    m: &MatchState,
+    member: Option<&UncheckedAccount>,
+    standing: Option<&UncheckedAccount>,
+) -> Result<Option<(Pubkey, GuildStanding)>> {
+    let member = member.ok_or(ArenaError::GuildRequired)?;
+    if member.data_is_empty() {
+        return Ok(None);
+    }
+    let guild = GuildMember::try_deserialize(&mut &member.try_borrow_data()?[..])?.guild;
+    let (expected, _) = Pubkey::find_program_address(
+        &[b"standing", m.tier.as_ref(), &m.day_id.to_le_bytes(), guild.as_ref()],
+        &crate::ID,
+    );
+    let standing = standing.ok_or(ArenaError::GuildRequired)?;
+    require_keys_eq!(standing.key(), expected, ArenaError::GuildRequired);
+    if standing.data_is_empty() {
+        return Ok(None);
+    }
+    let st = GuildStanding::try_deserialize(&mut &standing.try_borrow_data()?[..])?;
+    if st.members == 0 && m.guild_count >= MAX_GUILDS_PER_MATCH {
+        return Ok(None);
+    }
+    Ok(Some((guild, st)))
+}
+
+pub(crate) fn refund_waitlist(ctx: Context<RefundWaitlist>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, PAUSE_CLAIM)?;
+    let m = &ctx.accounts.match_state;
+    require!(m.status != MatchStatus::Open as u8, ArenaError::MatchStillOpen);
+    // Anchor's `close = player` returns every lamport in the waitlist account
+    Ok(())
+}
@@ -1,19 +1,20 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
-use pyth_sdk_solana::load_price_feed_from_account_info;
 
-declare_id!("UraChAoSArena111111111111111111111111111111");
+pub mod constants;
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub use ura_chaos_arena_math as math;
+pub mod state;
+mod utils;
+
+pub use constants::*;
+pub use errors::*;
+pub use events::*;
+pub use instructions::*;
+pub use state::*;
 
-// Constants to reduce code size
-const PCT_5: u128 = 5;
-const PCT_15: u128 = 15;
-const PCT_33: u128 = 33;
-const PCT_35: u128 = 35;
-const PCT_50: u128 = 50;
-const PCT_85: u128 = 85;
-const PCT_100: u128 = 100;
-const PYTH_STALENESS: u64 = 120;
-const PYTH_CONF_MAX: u128 = 5;
+declare_id!("UraChAoSArena111111111111111111111111111111");
 
 // Daily arena keyed by UTC day (unix_timestamp / 86400). Ticket purchases flow into a per-match
 // SOL vault PDA. At finalize, the program splits the pot: 85% prize pool, and 3x 5% buckets
@@ -26,577 +27,392 @@ pub mod ura_chaos_arena {
     pub fn initialize_config(
         ctx: Context<InitializeConfig>,
         revenue_wallet: Pubkey,
-        pyth_price_account: Pubkey,
+        pyth_feed_id: [u8; 32],
         min_ticket_lamports: u64,
     ) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        config.authority = ctx.accounts.authority.key();
-        config.revenue_wallet = revenue_wallet;
-        config.pyth_price_account = pyth_price_account;
-        config.min_ticket_lamports = min_ticket_lamports;
-        config.bump = *ctx.bumps.get("config").unwrap();
-        config.buyback_ura_bump = *ctx.bumps.get("buyback_ura_vault").unwrap();
-        config.buyback_urac_bump = *ctx.bumps.get("buyback_urac_vault").unwrap();
-
-        // Initialize stats (zero-initialization is automatic)
-        ctx.accounts.stats.bump = *ctx.bumps.get("stats").unwrap();
-        Ok(())
-    }
-
-    // Join the current UTC-day match; creates match + vault if needed.
-    // Transfers `amount` lamports from player to the match vault.
-    pub fn join(ctx: Context<Join>, amount: u64) -> Result<()> {
-        require!(amount > 0, ArenaError::InvalidAmount);
-        let config = &ctx.accounts.config;
-        let clock = Clock::get()?;
-        let price_feed = load_price_feed_from_account_info(&ctx.accounts.pyth_price_account)
-            .map_err(|_| ArenaError::PythError)?;
-        let price = price_feed.get_price_no_older_than(&clock, PYTH_STALENESS).ok_or(ArenaError::PythStale)?;
-        let abs_price = (price.price as i128).abs();
-        require!((price.conf as i128) * PCT_100 <= abs_price * PYTH_CONF_MAX, ArenaError::PythConfTooWide);
-        let min_lamports = lamports_for_usd_ceil(5, price.price, price.expo)?;
-        require!(amount >= min_lamports, ArenaError::TicketTooCheap);
-        // Optional safety floor
-        require!(amount >= config.min_ticket_lamports, ArenaError::TicketTooCheap);
-
-        let current_day = unix_day(clock.unix_timestamp);
-        let m = &mut ctx.accounts.match_state;
-
-        // Initialize if needed (most fields auto-zero)
-        if m.day_id == 0 {
-            m.day_id = current_day;
-            m.status = MatchStatus::Open as u8;
-            m.bump = *ctx.bumps.get("match_state").unwrap();
-        } else {
-            require!(m.day_id == current_day, ArenaError::WrongMatchForDay);
-            require!(m.status == MatchStatus::Open as u8, ArenaError::MatchClosed);
-        }
-
-        // Ensure no duplicate entry
-        let entry = &mut ctx.accounts.entry;
-        entry.match_key = m.key();
-        entry.player = ctx.accounts.player.key();
-        entry.paid = amount;
-        entry.joined_at = clock.unix_timestamp;
-        entry.bump = *ctx.bumps.get("entry").unwrap();
-
-        // Transfer lamports from player to match vault
-        let ix = system_instruction::transfer(&ctx.accounts.player.key(), &ctx.accounts.match_vault.key(), amount);
-        invoke_signed(
-            &ix,
-            &[
-                ctx.accounts.player.to_account_info(),
-                ctx.accounts.match_vault.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-            &[],
-        )?;
-
-        m.ticket_count = m.ticket_count.checked_add(1).ok_or(ArenaError::Overflow)?;
-        m.pot_lamports = m.pot_lamports.checked_add(amount).ok_or(ArenaError::Overflow)?;
-        Ok(())
+        instructions::initialize_config(ctx, revenue_wallet, pyth_feed_id, min_ticket_lamports)
+    }
+
+    // Grace window in which joins for the entry day that just ended are still accepted.
+    pub fn set_join_grace(ctx: Context<UpdateConfig>, grace_secs: u32) -> Result<()> {
+        instructions::set_join_grace(ctx, grace_secs)
+    }
+
+    // Pauses the instruction groups in `flags` (PAUSE_* bits). Callable by the authority or
+    // the guardian; only adds bits.
+    pub fn pause(ctx: Context<Pause>, flags: u8) -> Result<()> {
+        instructions::pause(ctx, flags)
+    }
+
+    // Authority replaces the whole bitmask, e.g. to unpause after a fix.
+    pub fn set_paused_flags(ctx: Context<UpdateConfig>, flags: u8) -> Result<()> {
+        instructions::set_paused_flags(ctx, flags)
+    }
+
+    // Layout migration: grows an account written by an older program version to its current
+    // layout and stamps ACCOUNT_VERSION. `kind` names the account type (see instructions::migrate).
+    // Permissionless; the payer covers any extra rent.
+    pub fn migrate(ctx: Context<MigrateAccount>, kind: u8) -> Result<()> {
+        instructions::migrate(ctx.accounts, kind)
+    }
+
+    // Queues a payout-critical config change (see AdminAction). It can be executed once the
+    // configured delay has passed, so players see upcoming changes before they apply.
+    pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+        instructions::queue_admin_action(ctx, action)
+    }
+
+    // Applies a queued action whose eta has passed and closes it.
+    pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+        instructions::execute_admin_action(ctx)
+    }
+
+    // Drops a queued action without applying it.
+    pub fn cancel_admin_action(ctx: Context<CancelAdminAction>) -> Result<()> {
+        instructions::cancel_admin_action(ctx)
+    }
+
+    // Opens the caller's ReferralAccount; its address is what a referred player passes to join.
+    pub fn create_referral(ctx: Context<CreateReferral>) -> Result<()> {
+        instructions::create_referral(ctx)
+    }
+
+    // Withdraws accrued rebates; the account keeps its rent.
+    pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+        instructions::claim_referral(ctx)
+    }
+
+    // Authority sets how join prices tickets. Thresholds are bounded so a typo cannot
+    // disable the staleness/confidence checks. In emergency mode each tier charges its
+    // fixed_lamports_per_ticket (AdminAction::SetEmergencyPrice). Not timelocked: it is the oracle-incident
+    // lever, and it only sets what new tickets cost, never how a pot is split.
+    pub fn set_oracle_policy(
+        ctx: Context<UpdateConfig>,
+        policy: u8,
+        max_age_secs: u64,
+        max_conf_bps: u16,
+    ) -> Result<()> {
+        instructions::set_oracle_policy(ctx, policy, max_age_secs, max_conf_bps)
+    }
+
+    // Authority sets the Pyth SOL/USD feed id join prices against. Required again after the
+    // config is migrated from the baseline layout, which held a price account in its place.
+    pub fn set_pyth_feed(ctx: Context<UpdateConfig>, feed_id: [u8; 32]) -> Result<()> {
+        instructions::set_pyth_feed(ctx, feed_id)
+    }
+
+    // Creates an arena tier (e.g. 0 = $5 daily, 1 = $50 high stakes). Each tier runs its own
+    // daily matches and vaults.
+    pub fn create_tier(ctx: Context<CreateTier>, tier_id: u8, ticket_price_usd_micros: u64) -> Result<()> {
+        instructions::create_tier(ctx, tier_id, ticket_price_usd_micros)
+    }
+
+    // Reprices a tier (takes effect for subsequent joins). The price charged while the oracle
+    // policy is EmergencyFixedPrice changes through the admin timelock (SetEmergencyPrice).
+    pub fn update_tier(ctx: Context<UpdateTier>, ticket_price_usd_micros: u64) -> Result<()> {
+        instructions::update_tier(ctx, ticket_price_usd_micros)
+    }
+
+    // Caps distinct players per match (0 = unlimited). With the waitlist enabled, players who
+    // find the match full can queue with escrowed funds; raising the cap lets them in.
+    pub fn set_player_cap(ctx: Context<UpdateTier>, max_players: u32, waitlist_enabled: bool) -> Result<()> {
+        instructions::set_player_cap(ctx, max_players, waitlist_enabled)
+    }
+
+    // Entries for a day close `join_cutoff_secs` before it ends, so players cannot join after
+    // seeing most of the day's PnL. With `entry_window_secs` > 0, joins made that long before
+    // midnight go to the next day's match (pre-registration). The window may not reach back
+    // past today's cutoff, so today's match always gets its full entry period.
+    pub fn set_entry_window(ctx: Context<UpdateTier>, join_cutoff_secs: u32, entry_window_secs: u32) -> Result<()> {
+        instructions::set_entry_window(ctx, join_cutoff_secs, entry_window_secs)
+    }
+
+    // Discount on season passes for this tier, in bps of the ticket price.
+    pub fn set_pass_discount(ctx: Context<UpdateTier>, discount_bps: u16) -> Result<()> {
+        instructions::set_pass_discount(ctx, discount_bps)
+    }
+
+    // How many days ahead join_future may sell tickets for this tier (0 disables it).
+    pub fn set_max_days_ahead(ctx: Context<UpdateTier>, max_days_ahead: u8) -> Result<()> {
+        instructions::set_max_days_ahead(ctx, max_days_ahead)
+    }
+
+    // Allows or forbids buying more than one ticket per player per match in this tier.
+    pub fn set_reentry(ctx: Context<UpdateTier>, allow: bool) -> Result<()> {
+        instructions::set_reentry(ctx, allow)
+    }
+
+    // Restricts the tier to holders (see TokenGate). For VerifiedCollection `mint` is the
+    // collection mint and `min_amount` is ignored.
+    pub fn set_token_gate(ctx: Context<UpdateTier>, kind: u8, mint: Pubkey, min_amount: u64) -> Result<()> {
+        instructions::set_token_gate(ctx, kind, mint, min_amount)
+    }
+
+    // Opens a private tournament: a tier at [b"tier", organizer, tournament_id] owned by the organizer,
+    // priced at a fixed `entry_fee_lamports` and open only to wallets in the Merkle allowlist
+    // `merkle_root`. The organizer earns organizer_bps of each match's revenue bucket.
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        tournament_id: u64,
+        entry_fee_lamports: u64,
+        merkle_root: [u8; 32],
+    ) -> Result<()> {
+        instructions::create_tournament(ctx, tournament_id, entry_fee_lamports, merkle_root)
+    }
+
+    // Reprices the tournament and/or replaces its allowlist. Wallets already registered stay in;
+    // matches already open keep the fee they opened with.
+    pub fn update_tournament(ctx: Context<UpdateTournament>, entry_fee_lamports: u64, merkle_root: [u8; 32]) -> Result<()> {
+        instructions::update_tournament(ctx, entry_fee_lamports, merkle_root)
+    }
+
+    // Registers the player for the tournament by proving their wallet is in the allowlist.
+    // The resulting invite must be passed to every join into the tournament's matches.
+    pub fn register_tournament(ctx: Context<RegisterTournament>, proof: Vec<[u8; 32]>) -> Result<()> {
+        instructions::register_tournament(ctx, proof)
+    }
+
+    // Withdraws the organizer's accrued revenue; the tournament account keeps its rent.
+    pub fn claim_tournament_revenue(ctx: Context<ClaimTournamentRevenue>) -> Result<()> {
+        instructions::claim_tournament_revenue(ctx)
+    }
+
+    // Join the tier's match currently taking entries (today's, or tomorrow's during the
+    // pre-registration window); creates match + vault if needed. `day_id` names that match and
+    // is checked against the clock (see entry_time).
+    // Transfers `amount` lamports from player to the match vault. Joining again buys another
+    // ticket if the tier allows re-entry.
+    pub fn join(ctx: Context<Join>, day_id: i64, amount: u64) -> Result<()> {
+        instructions::join(ctx, day_id, amount)
+    }
+
+    // Join paying exactly one ticket at the current oracle price, so a stale client-side quote
+    // can never overpay. `max_lamports` is the caller's slippage guard.
+    pub fn join_exact(ctx: Context<Join>, day_id: i64, max_lamports: u64) -> Result<()> {
+        instructions::join_exact(ctx, day_id, max_lamports)
+    }
+
+    // Buys a ticket for a future day's match (up to the tier's max_days_ahead), priced at
+    // purchase time. Lets players lock in e.g. a week ahead and fills pots before a day opens.
+    pub fn join_future(ctx: Context<Join>, day_id: i64, amount: u64) -> Result<()> {
+        instructions::join_future(ctx, day_id, amount)
+    }
+
+    // Buys a 7- or 30-day season pass at the tier's discount, priced at purchase time. The
+    // lamports sit in the tier's pass vault until each credit is redeemed into a match.
+    // A pass expires after its days; a new one can be bought once the previous one is used up
+    // or expired, and an expired pass's unused credits are refunded first.
+    pub fn buy_pass(ctx: Context<BuyPass>, days: u16, max_lamports: u64) -> Result<()> {
+        instructions::buy_pass(ctx, days, max_lamports)
+    }
+
+    // Spends one pass credit to enter the match currently taking entries. The credit's value
+    // moves from the pass vault into that day's pot.
+    pub fn redeem_pass(ctx: Context<RedeemPass>, day_id: i64) -> Result<()> {
+        instructions::redeem_pass(ctx, day_id)
+    }
+
+    // Once a pass has expired, returns its unused credits from the pass vault and closes it.
+    pub fn refund_pass(ctx: Context<RefundPass>) -> Result<()> {
+        instructions::refund_pass(ctx)
+    }
+
+    // Queues for today's match once it is full. `amount` (at least one ticket) is escrowed in
+    // the waitlist PDA until the player is admitted or the match closes.
+    pub fn join_waitlist(ctx: Context<JoinWaitlist>, day_id: i64, amount: u64) -> Result<()> {
+        instructions::join_waitlist(ctx, day_id, amount)
+    }
+
+    // Admits the head of the waitlist once the match has room (e.g. the cap was raised).
+    // Permissionless so a keeper can crank it; the caller pays the entry's rent and the
+    // waitlist account's rent goes back to the player.
+    pub fn admit_from_waitlist(ctx: Context<AdmitFromWaitlist>) -> Result<()> {
+        instructions::admit_from_waitlist(ctx)
+    }
+
+    // Returns escrow + rent to a waitlisted player who was never admitted. Permissionless once
+    // the match has closed.
+    pub fn refund_waitlist(ctx: Context<RefundWaitlist>) -> Result<()> {
+        instructions::refund_waitlist(ctx)
     }
 
     // Finalizes the match for a specific UTC day. Splits non-prize buckets immediately and
     // records derived group sizes for later per-winner allocations.
     // A keeper should call this anytime after UTC midnight for the previous day.
     pub fn finalize_match(ctx: Context<FinalizeMatch>, day_id: i64) -> Result<()> {
-        let clock = Clock::get()?;
-        // You can only finalize a day whose end has passed (>= next midnight).
-        let now_day = unix_day(clock.unix_timestamp);
-        require!(day_id < now_day, ArenaError::TooEarlyToFinalize);
-
-        let m = &mut ctx.accounts.match_state;
-        require!(m.day_id == day_id, ArenaError::WrongMatchForDay);
-        require!(m.status == MatchStatus::Open as u8, ArenaError::MatchAlreadyFinalized);
-
-        let pot = m.pot_lamports;
-        require!(pot > 0, ArenaError::EmptyPot);
-
-        // Buckets: 85% prize, 5% each for URA, URACHAOS, revenue
-        let mut remaining = pot;
-        let revenue = (pot as u128 * PCT_5) / PCT_100;
-        let ura = (pot as u128 * PCT_5) / PCT_100;
-        let urac = (pot as u128 * PCT_5) / PCT_100;
-        // Note: To avoid rounding dust staying in vault, we compute prize as the exact remaining
-        // after moving out the three 5% buckets.
-
-        let revenue = revenue as u64;
-        let ura = ura as u64;
-        let urac = urac as u64;
-
-        // Compute lamports to transfer out of the match vault now
-        let out_total = revenue.checked_add(ura).and_then(|x| x.checked_add(urac)).ok_or(ArenaError::Overflow)?;
-        remaining = remaining.checked_sub(out_total).ok_or(ArenaError::Overflow)?;
-
-        // Transfers from match_vault (PDA) using its signer seeds
-        let vault_bump = *ctx.bumps.get("match_vault").unwrap();
-        let vault_seeds: &[&[u8]] = &[b"vault", &ctx.accounts.match_state.key().to_bytes(), &[vault_bump]];
-
-        // revenue to provided wallet
-        transfer_from_vault(
-            &ctx.accounts.match_vault,
-            &ctx.accounts.revenue_wallet,
-            &ctx.accounts.system_program,
-            revenue,
-            &[vault_seeds],
-        )?;
-
-        // buyback URA to PDA vault (escrow to be swapped/burned off-chain)
-        transfer_from_vault(
-            &ctx.accounts.match_vault,
-            &ctx.accounts.buyback_ura_vault,
-            &ctx.accounts.system_program,
-            ura,
-            &[vault_seeds],
-        )?;
-
-        // buyback URACHAOS to its PDA vault
-        transfer_from_vault(
-            &ctx.accounts.match_vault,
-            &ctx.accounts.buyback_urac_vault,
-            &ctx.accounts.system_program,
-            urac,
-            &[vault_seeds],
-        )?;
-
-        // Derive winners and group sizes based on ticket_count.
-        let total = m.ticket_count.max(1);
-        let winners_count = ceil_div(total as u64 * PCT_33, PCT_100) as u32;
-        let group2_count = (ceil_div(total as u64 * PCT_15, PCT_100) as i64 - 1).max(0) as u32;
-        let group2_count = group2_count.min(winners_count.saturating_sub(1));
-        let group3_count = winners_count.saturating_sub(1 + group2_count);
-
-        m.status = MatchStatus::Finalized as u8;
-        m.winners_count = winners_count;
-        m.group2_count = group2_count;
-        m.group3_count = group3_count;
-        m.prize_pool = remaining; // exact amount left in vault for winner claims
-
-        // Precompute remainder to be added to rank 1 allocation later (rounding effect)
-        m.remainder = compute_remainder_for_top1(remaining, winners_count, group2_count, group3_count);
-
-        // Update global stats
-        let stats = &mut ctx.accounts.stats;
-        stats.total_matches = stats.total_matches.saturating_add(1);
-        stats.total_players = stats.total_players.saturating_add(m.ticket_count as u64);
-        stats.total_prize_distributed = stats.total_prize_distributed.saturating_add(remaining as u128);
-        stats.total_ura_earmarked_sol = stats.total_ura_earmarked_sol.saturating_add(ura as u128);
-        stats.total_urac_earmarked_sol = stats.total_urac_earmarked_sol.saturating_add(urac as u128);
-
-        Ok(())
+        instructions::finalize_match(ctx, day_id)
     }
 
     // Records a per-winner allocation. Must be called by the authority after finalize.
     // Multiple calls: one per winner with their rank (1-based).
     pub fn record_allocation(ctx: Context<RecordAllocation>, rank: u32) -> Result<()> {
-        let m = &mut ctx.accounts.match_state;
-        require!(m.status == MatchStatus::Finalized as u8, ArenaError::MatchNotFinalized);
-        require!(rank >= 1 && rank <= m.winners_count, ArenaError::InvalidRank);
+        instructions::record_allocation(ctx, rank)
+    }
 
-        let amount = compute_rank_allocation(m.prize_pool, m.winners_count, m.group2_count, m.group3_count, rank, m.remainder)?;
-        require!(amount > 0, ArenaError::ZeroAllocation);
+    // Winner claims their SOL from the match vault after allocation is recorded.
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        instructions::claim(ctx)
+    }
 
-        let alloc = &mut ctx.accounts.allocation;
-        alloc.match_key = m.key();
-        alloc.player = ctx.accounts.winner.key();
-        alloc.amount = amount;
-        alloc.claimed = false;
-        alloc.bump = *ctx.bumps.get("allocation").unwrap();
+    // Creates the founder's guild and makes them its first member.
+    pub fn create_guild(ctx: Context<CreateGuild>, name: [u8; 32], open: bool) -> Result<()> {
+        instructions::create_guild(ctx, name, open)
+    }
 
-        // Count recorded allocations to track progress if desired
-        m.allocations_recorded = m.allocations_recorded.saturating_add(1);
-        Ok(())
+    pub fn set_guild_open(ctx: Context<UpdateGuild>, open: bool) -> Result<()> {
+        instructions::set_guild_open(ctx, open)
     }
 
-    // Winner claims their SOL from the match vault after allocation is recorded.
-    pub fn claim(ctx: Context<Claim>) -> Result<()> {
-        let alloc = &mut ctx.accounts.allocation;
-        require!(!alloc.claimed, ArenaError::AlreadyClaimed);
-        let amount = alloc.amount;
-        require!(amount > 0, ArenaError::ZeroAllocation);
-        require!(alloc.player == ctx.accounts.winner.key(), ArenaError::InvalidAllocationOwner);
-
-        // Transfer from vault PDA to winner
-        let vault_bump = *ctx.bumps.get("match_vault").unwrap();
-        let vault_seeds: &[&[u8]] = &[b"vault", &ctx.accounts.match_state.key().to_bytes(), &[vault_bump]];
-        transfer_from_vault(
-            &ctx.accounts.match_vault,
-            &ctx.accounts.winner,
-            &ctx.accounts.system_program,
-            amount,
-            &[vault_seeds],
-        )?;
-
-        alloc.claimed = true;
-        Ok(())
+    // Adds the player to the roster. Closed guilds need the founder's co-signature.
+    pub fn join_guild(ctx: Context<JoinGuild>) -> Result<()> {
+        instructions::join_guild(ctx)
     }
-}
 
-// Accounts
-#[derive(Accounts)]
-pub struct InitializeConfig<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    #[account(
-        init,
-        payer = authority,
-        seeds = [b"config"],
-        bump,
-        space = 8 + ArenaConfig::SIZE,
-    )]
-    pub config: Account<'info, ArenaConfig>,
-    // Stats PDA holding global counters
-    #[account(
-        init,
-        payer = authority,
-        seeds = [b"stats", config.key().as_ref()],
-        bump,
-        space = 8 + Stats::SIZE,
-    )]
-    pub stats: Account<'info, Stats>,
-    // Buyback vault PDAs to accumulate SOL for later off-chain buyback and burn operations
-    #[account(
-        init,
-        payer = authority,
-        seeds = [b"buyback_ura", config.key().as_ref()],
-        bump,
-        space = 0,
-    )]
-    pub buyback_ura_vault: SystemAccount<'info>,
-    #[account(
-        init,
-        payer = authority,
-        seeds = [b"buyback_urac", config.key().as_ref()],
-        bump,
-        space = 0,
-    )]
-    pub buyback_urac_vault: SystemAccount<'info>,
-
-    pub system_program: Program<'info, System>,
-}
+    // Leaving (or being removed by the founder) closes the roster entry. Entries already in a
+    // match keep their guild. The founder cannot leave.
+    pub fn leave_guild(ctx: Context<LeaveGuild>) -> Result<()> {
+        instructions::leave_guild(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct Join<'info> {
-    #[account(mut)]
-    pub player: Signer<'info>,
-    #[account(mut, seeds = [b"config"], bump = config.bump)]
-    pub config: Account<'info, ArenaConfig>,
-    #[account(
-        init_if_needed,
-        payer = player,
-        seeds = [b"match", &current_day_bytes()],
-        bump,
-        space = 8 + MatchState::SIZE,
-    )]
-    pub match_state: Account<'info, MatchState>,
-    #[account(
-        init_if_needed,
-        payer = player,
-        seeds = [b"vault", match_state.key().as_ref()],
-        bump,
-        space = 0,
-    )]
-    pub match_vault: SystemAccount<'info>,
-    #[account(
-        init,
-        payer = player,
-        seeds = [b"entry", match_state.key().as_ref(), player.key().as_ref()],
-        bump,
-        space = 8 + PlayerEntry::SIZE,
-    )]
-    pub entry: Account<'info, PlayerEntry>,
-    /// CHECK: Pyth SOL/USD price account
-    #[account(address = config.pyth_price_account)]
-    pub pyth_price_account: UncheckedAccount<'info>,
-
-    pub system_program: Program<'info, System>,
-}
+    // Opens the guild's standing for a team-arena day; any member can pay for it. Must exist
+    // before the guild's members join that day's match.
+    pub fn open_guild_standing(ctx: Context<OpenGuildStanding>, day_id: i64) -> Result<()> {
+        instructions::open_guild_standing(ctx, day_id)
+    }
 
-#[derive(Accounts)]
-pub struct FinalizeMatch<'info> {
-    pub authority: Signer<'info>,
-    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
-    pub config: Account<'info, ArenaConfig>,
-    #[account(mut, seeds = [b"stats", config.key().as_ref()], bump = stats.bump)]
-    pub stats: Account<'info, Stats>,
-    #[account(mut, seeds = [b"match", &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
-    pub match_state: Account<'info, MatchState>,
-    #[account(mut, seeds = [b"vault", match_state.key().as_ref()], bump)]
-    pub match_vault: SystemAccount<'info>,
-    #[account(mut, seeds = [b"buyback_ura", config.key().as_ref()], bump = config.buyback_ura_bump)]
-    pub buyback_ura_vault: SystemAccount<'info>,
-    #[account(mut, seeds = [b"buyback_urac", config.key().as_ref()], bump = config.buyback_urac_bump)]
-    pub buyback_urac_vault: SystemAccount<'info>,
-    /// CHECK: revenue wallet can be any system account
-    #[account(mut, address = config.revenue_wallet)]
-    pub revenue_wallet: UncheckedAccount<'info>,
-
-    pub system_program: Program<'info, System>,
-}
+    // Ranks the match's guilds once every winner's allocation is recorded. Permissionless: all
+    // of the match's standings must be passed as remaining accounts, so the ranking is fully
+    // determined on-chain (score, then fewer members, then guild key).
+    pub fn commit_guild_results<'c: 'info, 'info>(ctx: Context<'_, '_, 'c, 'info, CommitGuildResults<'info>>) -> Result<()> {
+        instructions::commit_guild_results(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct RecordAllocation<'info> {
-    pub authority: Signer<'info>,
-    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
-    pub config: Account<'info, ArenaConfig>,
-    #[account(mut, seeds = [b"match", &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
-    pub match_state: Account<'info, MatchState>,
-    /// CHECK: winner pubkey recorded in allocation
-    pub winner: UncheckedAccount<'info>,
-    #[account(
-        init,
-        payer = authority,
-        seeds = [b"alloc", match_state.key().as_ref(), winner.key().as_ref()],
-        bump,
-        space = 8 + WinnerAllocation::SIZE,
-    )]
-    pub allocation: Account<'info, WinnerAllocation>,
-
-    pub system_program: Program<'info, System>,
-}
+    // A member of a prize-winning guild claims an equal share of the guild's bucket.
+    pub fn claim_guild_prize(ctx: Context<ClaimGuildPrize>) -> Result<()> {
+        instructions::claim_guild_prize(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct Claim<'info> {
-    #[account(mut)]
-    pub winner: Signer<'info>,
-    #[account(mut, seeds = [b"match", &match_state.day_id.to_le_bytes()], bump = match_state.bump)]
-    pub match_state: Account<'info, MatchState>,
-    #[account(mut, seeds = [b"vault", match_state.key().as_ref()], bump)]
-    pub match_vault: SystemAccount<'info>,
-    #[account(mut, seeds = [b"alloc", match_state.key().as_ref(), winner.key().as_ref()], bump = allocation.bump)]
-    pub allocation: Account<'info, WinnerAllocation>,
-
-    pub system_program: Program<'info, System>,
-}
+    // Opens a 1v1 challenge and escrows the creator's stake. `opponent` = default lets anyone
+    // accept until `accept_within_secs` from now; the match then runs for `duration_secs`.
+    pub fn create_challenge(
+        ctx: Context<CreateChallenge>,
+        challenge_id: u64,
+        opponent: Pubkey,
+        stake: u64,
+        duration_secs: u32,
+        accept_within_secs: u32,
+    ) -> Result<()> {
+        instructions::create_challenge(ctx, challenge_id, opponent, stake, duration_secs, accept_within_secs)
+    }
 
-// State
-#[account]
-#[derive(Default)]
-pub struct ArenaConfig {
-    pub authority: Pubkey,
-    pub revenue_wallet: Pubkey,
-    pub pyth_price_account: Pubkey,
-    pub min_ticket_lamports: u64,
-    pub bump: u8,
-    pub buyback_ura_bump: u8,
-    pub buyback_urac_bump: u8,
-}
-impl ArenaConfig { pub const SIZE: usize = 32 + 32 + 32 + 8 + 3; }
-
-#[account]
-pub struct MatchState {
-    pub day_id: i64,
-    pub ticket_count: u32,
-    pub pot_lamports: u64,
-    pub status: u8, // 0 = Open, 1 = Finalized
-    pub bump: u8,
-    // Finalization data
-    pub winners_count: u32,
-    pub group2_count: u32,
-    pub group3_count: u32,
-    pub prize_pool: u64,
-    pub allocations_recorded: u32,
-    pub remainder: u64, // carry rounding remainder to rank 1
-}
-impl MatchState {
-    pub const SIZE: usize = 8 + 4 + 8 + 1 + 1 + 4 + 4 + 4 + 8 + 4 + 8;
-}
+    // Matches the stake before the deadline; the challenge window starts now.
+    pub fn accept_challenge(ctx: Context<AcceptChallenge>) -> Result<()> {
+        instructions::accept_challenge(ctx)
+    }
 
-#[account]
-#[derive(Default)]
-pub struct PlayerEntry {
-    pub match_key: Pubkey,
-    pub player: Pubkey,
-    pub paid: u64,
-    pub joined_at: i64,
-    pub bump: u8,
-}
-impl PlayerEntry { pub const SIZE: usize = 32 + 32 + 8 + 8 + 1; }
-
-#[account]
-#[derive(Default)]
-pub struct WinnerAllocation {
-    pub match_key: Pubkey,
-    pub player: Pubkey,
-    pub amount: u64,
-    pub claimed: bool,
-    pub bump: u8,
-}
-impl WinnerAllocation { pub const SIZE: usize = 32 + 32 + 8 + 2; }
-
-#[repr(u8)]
-pub enum MatchStatus { Open = 0, Finalized = 1 }
-
-#[account]
-#[derive(Default)]
-pub struct Stats {
-    pub total_matches: u64,
-    pub total_players: u64,
-    pub total_prize_distributed: u128,
-    pub total_ura_earmarked_sol: u128,
-    pub total_urac_earmarked_sol: u128,
-    pub total_ura_burned_atoms: u128,
-    pub total_urac_burned_atoms: u128,
-    pub total_ura_burn_sol: u64,
-    pub total_urac_burn_sol: u64,
-    pub bump: u8,
-}
-impl Stats { pub const SIZE: usize = 8 + 8 + 16*5 + 8 + 8 + 1; }
+    // Referee posts the winner once the window has ended: the pot minus the challenge fee goes
+    // to the winner, the fee to the revenue wallet and the rent back to the creator.
+    pub fn settle_challenge(ctx: Context<SettleChallenge>, winner: Pubkey) -> Result<()> {
+        instructions::settle_challenge(ctx, winner)
+    }
 
-// Utils
-fn unix_day(ts: i64) -> i64 { ts.div_euclid(86_400) }
+    // Refunds an unaccepted challenge: the creator can cancel at any time, anyone can once the
+    // acceptance deadline has passed.
+    pub fn cancel_challenge(ctx: Context<CancelChallenge>) -> Result<()> {
+        instructions::cancel_challenge(ctx)
+    }
 
-fn current_day_bytes() -> [u8; 8] {
-    let ts = Clock::get().unwrap().unix_timestamp; // only called in account seeds context during join
-    unix_day(ts).to_le_bytes()
-}
+    // Unwinds an accepted challenge the referee never settled: once CHALLENGE_SETTLE_GRACE_SECS
+    // have passed since it ended, either side returns both stakes, fee-free.
+    pub fn refund_challenge(ctx: Context<RefundChallenge>) -> Result<()> {
+        instructions::refund_challenge(ctx)
+    }
 
-fn ceil_div(numer: u64, denom: u64) -> u64 { (numer + denom - 1) / denom }
+    // Opens a spectator pool on an open match. Permissionless; one pool per match and kind.
+    pub fn create_prediction_pool(ctx: Context<CreatePredictionPool>, kind: u8) -> Result<()> {
+        instructions::create_prediction_pool(ctx, kind)
+    }
 
-fn compute_remainder_for_top1(prize: u64, winners: u32, group2: u32, group3: u32) -> u64 {
-    if winners == 0 { return 0; }
-    let top1 = (prize as u128 * 50) / 100;
-    let g2_total = (prize as u128 * 35) / 100;
-    let g3_total = (prize as u128 * 15) / 100;
+    // Stakes `amount` on an entrant. Only wallets without an entry in the match may bet, and
+    // betting closes with the match's entries (the tier's join cutoff).
+    pub fn place_bet(ctx: Context<PlaceBet>, amount: u64) -> Result<()> {
+        instructions::place_bet(ctx, amount)
+    }
 
-    let g2_each = if group2 > 0 { g2_total / group2 as u128 } else { 0 };
-    let g3_each = if group3 > 0 { g3_total / group3 as u128 } else { 0 };
+    // Permissionless. Resolves one winning rank of the pool from the recorded allocation: the
+    // entrant's outcome (if anyone bet on them) is marked won. Resolving the last winning rank
+    // settles the pool and routes the fee into the buyback vaults.
+    pub fn resolve_prediction(ctx: Context<ResolvePrediction>) -> Result<()> {
+        instructions::resolve_prediction(ctx)
+    }
 
-    // Sum of floors
-    let sum = top1 + g2_each * group2 as u128 + g3_each * group3 as u128;
-    let prize_u128 = prize as u128;
-    let rem = prize_u128.saturating_sub(sum);
-    rem as u64
-}
+    // Pays a settled bet pari-mutuel: winning bets share the pool after fee pro rata to their
+    // stake. Closes the bet to the bettor.
+    pub fn claim_prediction(ctx: Context<ClaimPrediction>) -> Result<()> {
+        instructions::claim_prediction(ctx)
+    }
 
-fn compute_rank_allocation(
-    prize: u64,
-    winners: u32,
-    group2: u32,
-    group3: u32,
-    rank: u32,
-    remainder_for_top1: u64,
-) -> Result<u64> {
-    require!(rank >= 1 && rank <= winners, ArenaError::InvalidRank);
-    let p = prize as u128;
-    if rank == 1 {
-        let base = (p * 50) / 100;
-        let amt = base as u64 + remainder_for_top1; // carry rounding remainder to top1
-        return Ok(amt);
-    }
-    if rank <= 1 + group2 && group2 > 0 {
-        let g2_total = (p * 35) / 100;
-        let each = (g2_total / group2 as u128) as u64;
-        return Ok(each);
-    }
-    if group3 > 0 {
-        let g3_total = (p * 15) / 100;
-        let each = (g3_total / group3 as u128) as u64;
-        return Ok(each);
-    }
-    Ok(0)
-}
+    // Returns a bet's stake once its pool has missed the settlement deadline (for instance
+    // because a winning rank's allocation was never recorded). Closes the bet to the bettor.
+    pub fn refund_bet(ctx: Context<RefundBet>) -> Result<()> {
+        instructions::refund_bet(ctx)
+    }
 
-const LAMPORTS_PER_SOL_CONST: i128 = 1_000_000_000;
-
-fn lamports_for_usd_ceil(usd_whole: u64, price: i64, expo: i32) -> Result<u64> {
-    require!(price > 0, ArenaError::PythError);
-    let price_i = price as i128;
-    let usd_i = usd_whole as i128;
-
-    let res_i: i128 = if expo < 0 {
-        let scale = ten_pow_i128((-expo) as u32)?;
-        let numer = usd_i
-            .checked_mul(LAMPORTS_PER_SOL_CONST).ok_or(ArenaError::Overflow)?
-            .checked_mul(scale).ok_or(ArenaError::Overflow)?;
-        ceil_div_i128(numer, price_i)?
-    } else if expo > 0 {
-        let scale = ten_pow_i128(expo as u32)?;
-        let denom = price_i.checked_mul(scale).ok_or(ArenaError::Overflow)?;
-        let numer = usd_i
-            .checked_mul(LAMPORTS_PER_SOL_CONST).ok_or(ArenaError::Overflow)?;
-        ceil_div_i128(numer, denom)?
-    } else {
-        let numer = usd_i
-            .checked_mul(LAMPORTS_PER_SOL_CONST).ok_or(ArenaError::Overflow)?;
-        ceil_div_i128(numer, price_i)?
-    };
-
-    u64::try_from(res_i).map_err(|_| error!(ArenaError::Overflow))
-}
+    // Creates the URACHAOS staking pool and its token vault, and turns on the revenue share.
+    // `revenue_bps` and `cooldown_secs` are initial values; later changes go through the admin
+    // timelock.
+    pub fn init_staking(ctx: Context<InitStaking>, revenue_bps: u16, cooldown_secs: u32) -> Result<()> {
+        instructions::init_staking(ctx, revenue_bps, cooldown_secs)
+    }
 
-fn ten_pow_i128(exp: u32) -> Result<i128> {
-    let mut r: i128 = 1;
-    for _ in 0..exp {
-        r = r.checked_mul(10).ok_or(ArenaError::Overflow)?;
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        instructions::stake(ctx, amount)
     }
-    Ok(r)
-}
 
-fn ceil_div_i128(numer: i128, denom: i128) -> Result<i128> {
-    require!(denom > 0, ArenaError::Overflow);
-    Ok((numer + denom - 1) / denom)
-}
+    // Starts the cooldown for `amount`; those tokens stop earning immediately. Requesting again
+    // adds to the amount cooling down and restarts the cooldown.
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        instructions::request_unstake(ctx, amount)
+    }
 
-// Authority posts buy+burn results to stats
-#[derive(Accounts)]
-pub struct RecordBurned<'info> {
-    pub authority: Signer<'info>,
-    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
-    pub config: Account<'info, ArenaConfig>,
-    #[account(mut, seeds = [b"stats", config.key().as_ref()], bump = stats.bump)]
-    pub stats: Account<'info, Stats>,
-}
+    // Returns tokens whose cooldown has elapsed.
+    pub fn withdraw_unstaked(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+        instructions::withdraw_unstaked(ctx)
     }
 
-    pub fn record_burned(ctx: Context<RecordBurned>, ura_burned_atoms: u128, urac_burned_atoms: u128, ura_sol_spent: u64, urac_sol_spent: u64) -> Result<()> {
-        let stats = &mut ctx.accounts.stats;
-        stats.total_ura_burned_atoms = stats.total_ura_burned_atoms.saturating_add(ura_burned_atoms);
-        stats.total_urac_burned_atoms = stats.total_urac_burned_atoms.saturating_add(urac_burned_atoms);
-        stats.total_ura_burn_sol = stats.total_ura_burn_sol.saturating_add(ura_sol_spent);
-        stats.total_urac_burn_sol = stats.total_urac_burn_sol.saturating_add(urac_sol_spent);
-        Ok(())
+    // Pays out the position's accrued SOL rewards.
+    pub fn claim_staking_rewards(ctx: Context<ClaimStakingRewards>) -> Result<()> {
+        instructions::claim_staking_rewards(ctx)
     }
-}
 
-fn transfer_from_vault(
-    from: &SystemAccount,
-    to: &impl ToAccountInfo,
-    system_program: &Program<System>,
-    lamports: u64,
-    signer_seeds: &[&[&[u8]]],
-) -> Result<()> {
-    if lamports == 0 { return Ok(()); }
-    let ix = system_instruction::transfer(&from.key(), &to.to_account_info().key(), lamports);
-    invoke_signed(
-        &ix,
-        &[
-            from.to_account_info(),
-            to.to_account_info(),
-            system_program.to_account_info(),
-        ],
-        signer_seeds,
-    )?;
-    Ok(())
-}
+    // Defines an achievement unlocked once a PlayerProfile metric reaches `threshold`.
+    pub fn create_achievement(ctx: Context<CreateAchievement>, achievement_id: u16, metric: u8, threshold: u64) -> Result<()> {
+        instructions::create_achievement(ctx, achievement_id, metric, threshold)
+    }
+
+    // Existing unlocks stay valid when the threshold changes or the achievement is retired.
+    pub fn update_achievement(ctx: Context<UpdateAchievement>, threshold: u64, active: bool) -> Result<()> {
+        instructions::update_achievement(ctx, threshold, active)
+    }
 
-#[error_code]
-pub enum ArenaError {
-    #[msg("invalid amount")] InvalidAmount,
-    #[msg("ticket amount below minimum")] TicketTooCheap,
-    #[msg("wrong match account for current day")] WrongMatchForDay,
-    #[msg("match already closed or not open")] MatchClosed,
-    #[msg("too early to finalize")] TooEarlyToFinalize,
-    #[msg("match already finalized")] MatchAlreadyFinalized,
-    #[msg("pot is empty")] EmptyPot,
-    #[msg("overflow")] Overflow,
-    #[msg("invalid rank")] InvalidRank,
-    #[msg("zero allocation")] ZeroAllocation,
-    #[msg("already claimed")] AlreadyClaimed,
-    #[msg("match not finalized")] MatchNotFinalized,
-    #[msg("pyth price error")] PythError,
-    #[msg("pyth price stale")] PythStale,
-    #[msg("pyth confidence too wide")] PythConfTooWide,
-    #[msg("allocation not owned by winner")] InvalidAllocationOwner,
+    // Creates the achievement's soulbound badge: a Token-2022 mint with the NonTransferable
+    // extension, 0 decimals, minted by the achievement PDA.
+    pub fn enable_achievement_badge(ctx: Context<EnableAchievementBadge>) -> Result<()> {
+        instructions::enable_achievement_badge(ctx)
+    }
+
+    // Permissionless: records the unlock once the player's profile meets the achievement.
+    pub fn unlock_achievement(ctx: Context<UnlockAchievement>) -> Result<()> {
+        instructions::unlock_achievement(ctx)
+    }
+
+    // Mints the badge for an unlocked achievement to the player's associated token account.
+    // Permissionless; one badge per unlock.
+    pub fn mint_badge(ctx: Context<MintBadge>) -> Result<()> {
+        instructions::mint_badge(ctx)
+    }
+
+    pub fn record_burned(ctx: Context<RecordBurned>, ura_burned_atoms: u128, urac_burned_atoms: u128, ura_sol_spent: u64, urac_sol_spent: u64) -> Result<()> {
+        instructions::record_burned(ctx, ura_burned_atoms, urac_burned_atoms, ura_sol_spent, urac_sol_spent)
+    }
 }
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+// Config change waiting out the admin timelock
+#[account]
+pub struct PendingAdminAction {
+    pub id: u64,
+    pub action: AdminAction,
+    pub eta: i64, // earliest execution time
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl PendingAdminAction { pub const SIZE: usize = 8 + AdminAction::SIZE + 8 + 1 + 1 + RESERVED_BYTES; }
+
+// Payout-critical config changes, applied only through the admin timelock. SetAuthority also
+// moves the referee key (the authority signs finalize, allocations and challenge results), and
+// SetGuardian the key that can pause. The tier variants name the tier they change; it is passed
+// to execute_admin_action.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum AdminAction {
+    SetAuthority(Pubkey),
+    SetRevenueWallet(Pubkey),
+    SetReferralBps(u16),
+    SetChallengeFee(u16),
+    SetPredictionFee(u16),
+    SetOrganizerBps(u16),
+    SetStakingRevenueBps(u16),
+    SetTimelockDelay(u32),
+    SetStakeWeighting(Pubkey, u16),
+    SetTeamMode(Pubkey, u16),
+    SetGuardian(Pubkey),
+    SetStakingCooldown(u32),
+    SetEmergencyPrice(Pubkey, u64),
+}
+
+impl AdminAction {
+    pub const SIZE: usize = 1 + 32 + 8; // tag + largest payload
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+#[account]
+#[derive(Default)]
+pub struct MatchState {
+    pub day_id: i64,
+    pub ticket_count: u32, // tickets sold, including re-entries
+    pub pot_lamports: u64,
+    pub status: u8, // 0 = Open, 1 = Finalized
+    pub bump: u8,
+    // Finalization data
+    pub winners_count: u32,
+    pub group2_count: u32,
+    pub group3_count: u32,
+    pub prize_pool: u64,
+    pub allocations_recorded: u32,
+    pub remainder: u64, // carry rounding remainder to rank 1
+    pub tier: Pubkey,
+    // Stake weighting, copied from the tier when the match opens (0 = rank only)
+    pub stake_weight_cap: u16,
+    pub total_stake_score: u128, // sum of recorded winners' rank share x weight
+    pub player_count: u32, // distinct players; winners are derived from this
+    // Waitlist positions: count handed out so far, and the next one to admit (FIFO)
+    pub waitlist_count: u32,
+    pub waitlist_head: u32,
+    // Referral rebates already paid out of the revenue bucket (not held in the vault)
+    pub referral_lamports: u64,
+    // Team arena, copied from the tier when the match opens (0 = solo)
+    pub guild_prize_bps: u16,
+    pub guild_count: u32, // guilds with at least one entry
+    pub guild_pool: u64, // carved out of the prize pool at finalize
+    pub guild_results_committed: bool,
+    pub organizer_bps: u16, // tournament matches: copied from config when the match opens
+    pub version: u8,
+    pub entry_fee_lamports: u64, // tournament matches: copied from the tier when the match opens; taken from the reserved bytes
+    pub reserved: [u8; 24],
+}
+
+impl MatchState {
+    // What is left of RESERVED_BYTES after entry_fee_lamports
+    pub const RESERVED: usize = RESERVED_BYTES - 8;
+    pub const SIZE: usize = 8 + 4 + 8 + 1 + 1 + 4 + 4 + 4 + 8 + 4 + 8 + 32 + 2 + 16 + 4 + 4 + 4 + 8 + 2 + 4 + 8 + 1 + 2 + 1 + 8 + Self::RESERVED;
+}
+
+#[repr(u8)]
+pub enum MatchStatus { Open = 0, Finalized = 1 }
+
+#[account]
+#[derive(Default)]
+pub struct PlayerEntry {
+    pub match_key: Pubkey,
+    pub player: Pubkey,
+    pub paid: u64, // cumulative over all tickets
+    pub joined_at: i64, // first ticket
+    pub bump: u8,
+    pub weight: u32, // stake weight in 1/10_000 tickets (10_000 = one minimum ticket)
+    pub tickets: u32,
+    pub guild: Pubkey, // team arenas only; default = solo
+    pub guild_claimed: bool,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl PlayerEntry { pub const SIZE: usize = 32 + 32 + 8 + 8 + 1 + 4 + 4 + 32 + 1 + 1 + RESERVED_BYTES; }
+
+#[account]
+#[derive(Default)]
+pub struct WinnerAllocation {
+    pub match_key: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64, // fixed at claim for stake-weighted matches
+    pub claimed: bool,
+    pub bump: u8,
+    pub rank: u32,
+    pub weight: u32,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl WinnerAllocation { pub const SIZE: usize = 32 + 32 + 8 + 2 + 4 + 4 + 1 + RESERVED_BYTES; }
+
+// Queued player for a full match; holds the escrowed ticket lamports on top of its rent
+#[account]
+#[derive(Default)]
+pub struct WaitlistEntry {
+    pub match_key: Pubkey,
+    pub player: Pubkey,
+    pub escrow: u64,
+    pub ticket: u64, // ticket price when queued, for stake weighting on admission
+    pub queued_at: i64,
+    pub position: u32,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl WaitlistEntry { pub const SIZE: usize = 32 + 32 + 8 + 8 + 8 + 4 + 1 + 1 + RESERVED_BYTES; }
+
+// Prepaid daily entries for one tier; each credit is worth lamports_per_credit in the pass vault
+#[account]
+#[derive(Default)]
+pub struct SeasonPass {
+    pub player: Pubkey,
+    pub tier: Pubkey,
+    pub credits: u16,
+    pub lamports_per_credit: u64,
+    pub purchased_at: i64,
+    pub bump: u8,
+    pub version: u8,
+    pub expires_at: i64, // end of the pass's last day; taken from the reserved bytes
+    pub reserved: [u8; 24],
+}
+
+impl SeasonPass {
+    // What is left of RESERVED_BYTES after expires_at
+    pub const RESERVED: usize = RESERVED_BYTES - 8;
+    pub const SIZE: usize = 32 + 32 + 2 + 8 + 8 + 1 + 1 + 8 + Self::RESERVED;
+}
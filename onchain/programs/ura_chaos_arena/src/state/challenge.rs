@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+// 1v1 match outside the daily arena. Both stakes are escrowed in this account; it is closed
+// to the creator on settlement, cancellation or refund.
+#[account]
+#[derive(Default)]
+pub struct Challenge {
+    pub creator: Pubkey,
+    pub challenge_id: u64,
+    pub opponent: Pubkey, // fixed opponent, or default = anyone may accept
+    pub stake: u64, // per side
+    pub duration_secs: u32,
+    pub accept_deadline: i64,
+    pub ends_at: i64, // set on accept
+    pub status: u8, // ChallengeStatus
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl Challenge { pub const SIZE: usize = 32 + 8 + 32 + 8 + 4 + 8 + 8 + 1 + 1 + 1 + RESERVED_BYTES; }
+
+#[repr(u8)]
+pub enum ChallengeStatus { Open = 0, Active = 1 }
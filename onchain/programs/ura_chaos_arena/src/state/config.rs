@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+#[account]
+#[derive(Default)]
+pub struct ArenaConfig {
+    pub authority: Pubkey,
+    pub revenue_wallet: Pubkey,
+    pub pyth_feed_id: [u8; 32],
+    pub min_ticket_lamports: u64,
+    pub bump: u8,
+    pub buyback_ura_bump: u8,
+    pub buyback_urac_bump: u8,
+    // Oracle policy (see OraclePolicy) and its thresholds
+    pub oracle_policy: u8,
+    pub pyth_max_age_secs: u64,
+    pub pyth_max_conf_bps: u16,
+    pub referral_bps: u16, // share of each referred ticket's revenue bucket paid to the referrer
+    pub challenge_fee_bps: u16,
+    pub prediction_fee_bps: u16,
+    pub staking_enabled: bool, // finalize_match must then pass the staking pool
+    pub organizer_bps: u16, // share of a tournament match's revenue bucket paid to its organizer
+    pub paused_flags: u8, // PAUSE_* bits
+    pub guardian: Pubkey, // may set pause bits, never clear them
+    pub admin_timelock_secs: u32, // delay between queue_admin_action and execution
+    pub admin_action_count: u64, // id of the next queued action
+    pub version: u8,
+    pub join_grace_secs: u32, // see entry_time; taken from the reserved bytes
+    pub reserved: [u8; 28],
+}
+
+impl ArenaConfig {
+    // What is left of RESERVED_BYTES after join_grace_secs
+    pub const RESERVED: usize = RESERVED_BYTES - 4;
+    pub const SIZE: usize = 32 + 32 + 32 + 8 + 3 + 1 + 8 + 2 + 2 + 2 + 2 + 1 + 2 + 1 + 32 + 4 + 8 + 1 + 4 + Self::RESERVED;
+}
+
+#[account]
+#[derive(Default)]
+pub struct Stats {
+    pub total_matches: u64,
+    pub total_players: u64,
+    pub total_prize_distributed: u128,
+    pub total_ura_earmarked_sol: u128,
+    pub total_urac_earmarked_sol: u128,
+    pub total_ura_burned_atoms: u128,
+    pub total_urac_burned_atoms: u128,
+    pub total_ura_burn_sol: u64,
+    pub total_urac_burn_sol: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl Stats { pub const SIZE: usize = 8 + 8 + 16*5 + 8 + 8 + 1 + 1 + RESERVED_BYTES; }
+
+// Strict: reject stale or wide-confidence quotes.
+// Lenient: still reject stale quotes, but price wide-confidence quotes at the low end of the
+//          band (price - conf) so the ticket is never undercharged.
+// EmergencyFixedPrice: ignore the oracle and charge each tier's fixed_lamports_per_ticket.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum OraclePolicy { Strict = 0, Lenient = 1, EmergencyFixedPrice = 2 }
+
+impl OraclePolicy {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Strict),
+            1 => Some(Self::Lenient),
+            2 => Some(Self::EmergencyFixedPrice),
+            _ => None,
+        }
+    }
+}
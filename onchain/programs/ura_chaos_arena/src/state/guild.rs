@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+#[account]
+#[derive(Default)]
+pub struct Guild {
+    pub founder: Pubkey,
+    pub name: [u8; 32], // utf-8, zero padded
+    pub open: bool, // anyone may join; otherwise the founder co-signs
+    pub member_count: u32,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl Guild { pub const SIZE: usize = 32 + 32 + 1 + 4 + 1 + 1 + RESERVED_BYTES; }
+
+// Roster entry; a wallet belongs to at most one guild
+#[account]
+#[derive(Default)]
+pub struct GuildMember {
+    pub guild: Pubkey,
+    pub player: Pubkey,
+    pub joined_at: i64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl GuildMember { pub const SIZE: usize = 32 + 32 + 8 + 1 + 1 + RESERVED_BYTES; }
+
+// A guild's result in one team-arena match, keyed by tier and day so it can be opened before
+// the match account exists.
+#[account]
+#[derive(Default)]
+pub struct GuildStanding {
+    pub tier: Pubkey,
+    pub day_id: i64,
+    pub guild: Pubkey,
+    pub members: u32, // members entered
+    pub score: u64, // sum over the guild's winners of (winners_count + 1 - rank)
+    pub rank: u32, // 1..=3 for prize-winning guilds after commit, else 0
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl GuildStanding { pub const SIZE: usize = 32 + 8 + 32 + 4 + 8 + 4 + 1 + 1 + RESERVED_BYTES; }
@@ -0,0 +1,161 @@
+mod admin;
+mod arena;
+mod challenge;
+mod config;
+mod external;
+mod guild;
+mod prediction;
+mod profile;
+mod staking;
+mod tier;
+mod tournament;
+
+pub use admin::*;
+pub use arena::*;
+pub use challenge::*;
+pub use config::*;
+pub use external::*;
+pub use guild::*;
+pub use prediction::*;
+pub use profile::*;
+pub use staking::*;
+pub use tier::*;
+pub use tournament::*;
+
+// Accounts the migrate instruction can grow in place
+pub trait Versioned {
+    const SPACE: usize;
+    fn version_mut(&mut self) -> &mut u8;
+    // Defaults for settings added since `from_version` wrote the account, applied on migration
+    fn fill_defaults(&mut self, _from_version: u8) {}
+}
+
+macro_rules! versioned {
+    ($($t:ty),* $(,)?) => {$(
+        impl Versioned for $t {
+            const SPACE: usize = 8 + <$t>::SIZE;
+            fn version_mut(&mut self) -> &mut u8 { &mut self.version }
+        }
+    )*};
+}
+
+// Configs written before the oracle thresholds and timelock delay had defaults would otherwise
+// keep zeros (every quote stale, no delay)
+impl Versioned for ArenaConfig {
+    const SPACE: usize = 8 + ArenaConfig::SIZE;
+    fn version_mut(&mut self) -> &mut u8 { &mut self.version }
+    fn fill_defaults(&mut self, from_version: u8) {
+        use crate::constants::*;
+        // The baseline layout stored a Pyth price account where pyth_feed_id now sits. Those
+        // bytes are not a feed id, so oracle joins stay closed until set_pyth_feed names one.
+        if from_version == 0 {
+            self.pyth_feed_id = [0; 32];
+        }
+        if self.pyth_max_age_secs == 0 {
+            self.pyth_max_age_secs = PYTH_STALENESS_DEFAULT;
+        }
+        if self.pyth_max_conf_bps == 0 {
+            self.pyth_max_conf_bps = PYTH_CONF_BPS_DEFAULT;
+        }
+        if self.admin_timelock_secs == 0 {
+            self.admin_timelock_secs = ADMIN_TIMELOCK_DEFAULT_SECS;
+        }
+    }
+}
+
+versioned!(
+    Stats,
+    PendingAdminAction,
+    ArenaTier,
+    MatchState,
+    PlayerEntry,
+    PlayerProfile,
+    ReferralAccount,
+    Tournament,
+    TournamentInvite,
+    Guild,
+    GuildMember,
+    GuildStanding,
+    Challenge,
+    StakingPool,
+    StakePosition,
+    PredictionPool,
+    PredictionOutcome,
+    PredictionBet,
+    Achievement,
+    AchievementUnlock,
+    SeasonPass,
+    WaitlistEntry,
+    WinnerAllocation,
+    BetMark,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+    use anchor_lang::prelude::*;
+
+    #[test]
+    fn account_sizes_match_layouts() {
+        fn len<T: AnchorSerialize + Default>() -> usize { T::default().try_to_vec().unwrap().len() }
+        assert_eq!(len::<ArenaConfig>(), ArenaConfig::SIZE);
+        assert_eq!(len::<Stats>(), Stats::SIZE);
+        assert_eq!(len::<ArenaTier>(), ArenaTier::SIZE);
+        assert_eq!(len::<MatchState>(), MatchState::SIZE);
+        assert_eq!(len::<PlayerEntry>(), PlayerEntry::SIZE);
+        assert_eq!(len::<PlayerProfile>(), PlayerProfile::SIZE);
+        assert_eq!(len::<ReferralAccount>(), ReferralAccount::SIZE);
+        assert_eq!(len::<Tournament>(), Tournament::SIZE);
+        assert_eq!(len::<TournamentInvite>(), TournamentInvite::SIZE);
+        assert_eq!(len::<Guild>(), Guild::SIZE);
+        assert_eq!(len::<GuildMember>(), GuildMember::SIZE);
+        assert_eq!(len::<GuildStanding>(), GuildStanding::SIZE);
+        assert_eq!(len::<Challenge>(), Challenge::SIZE);
+        assert_eq!(len::<StakingPool>(), StakingPool::SIZE);
+        assert_eq!(len::<StakePosition>(), StakePosition::SIZE);
+        assert_eq!(len::<PredictionPool>(), PredictionPool::SIZE);
+        assert_eq!(len::<PredictionOutcome>(), PredictionOutcome::SIZE);
+        assert_eq!(len::<PredictionBet>(), PredictionBet::SIZE);
+        assert_eq!(len::<Achievement>(), Achievement::SIZE);
+        assert_eq!(len::<AchievementUnlock>(), AchievementUnlock::SIZE);
+        assert_eq!(len::<SeasonPass>(), SeasonPass::SIZE);
+        assert_eq!(len::<WaitlistEntry>(), WaitlistEntry::SIZE);
+        assert_eq!(len::<WinnerAllocation>(), WinnerAllocation::SIZE);
+        assert_eq!(len::<BetMark>(), BetMark::SIZE);
+    }
+
+    #[test]
+    fn reserved_space_keeps_layouts() {
+        // A field taken from the reserved bytes must shrink RESERVED by its size, leaving the
+        // account size (and every later offset) as deployed
+        assert_eq!(ArenaConfig::default().reserved.len(), ArenaConfig::RESERVED);
+        assert_eq!(ArenaConfig::SIZE, 205);
+        assert_eq!(MatchState::default().reserved.len(), MatchState::RESERVED);
+        assert_eq!(MatchState::SIZE, 174);
+        assert_eq!(SeasonPass::default().reserved.len(), SeasonPass::RESERVED);
+        assert_eq!(SeasonPass::SIZE, 116);
+    }
+
+    #[test]
+    fn baseline_config_migration_clears_the_feed() {
+        // A baseline config: the old price account's bytes sit where pyth_feed_id is read
+        let mut config = ArenaConfig { pyth_feed_id: [7; 32], ..Default::default() };
+        config.fill_defaults(0);
+        assert_eq!(config.pyth_feed_id, [0; 32]);
+        assert_eq!((config.pyth_max_age_secs, config.pyth_max_conf_bps), (PYTH_STALENESS_DEFAULT, PYTH_CONF_BPS_DEFAULT));
+        assert_eq!(config.admin_timelock_secs, ADMIN_TIMELOCK_DEFAULT_SECS);
+        // A versioned config keeps the feed and thresholds it was given
+        let mut config = ArenaConfig { pyth_feed_id: [7; 32], pyth_max_age_secs: 60, ..Default::default() };
+        config.fill_defaults(1);
+        assert_eq!((config.pyth_feed_id, config.pyth_max_age_secs), ([7; 32], 60));
+    }
+
+    #[test]
+    fn profile_referrer_offset() {
+        // src/chain/arena.ts reads the referrer at byte 113 of the account (after the discriminator)
+        let p = PlayerProfile { referrer: Pubkey::new_unique(), ..Default::default() };
+        let data = p.try_to_vec().unwrap();
+        assert_eq!(&data[113 - 8..113 - 8 + 32], p.referrer.as_ref());
+    }
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+// Spectator pool on one match; holds all stakes until claimed
+#[account]
+#[derive(Default)]
+pub struct PredictionPool {
+    pub match_key: Pubkey,
+    pub kind: u8, // PredictionKind
+    pub total_staked: u64,
+    pub winning_total: u64, // stakes on winning entrants, summed while resolving
+    pub resolved_ranks: u8, // bit (rank - 1) set once that rank's outcome is resolved
+    pub settled: bool,
+    pub fee: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl PredictionPool { pub const SIZE: usize = 32 + 1 + 8 + 8 + 1 + 1 + 8 + 1 + 1 + RESERVED_BYTES; }
+
+// Stakes on one entrant within a pool
+#[account]
+#[derive(Default)]
+pub struct PredictionOutcome {
+    pub pool: Pubkey,
+    pub entrant: Pubkey,
+    pub total_staked: u64,
+    pub won: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl PredictionOutcome { pub const SIZE: usize = 32 + 32 + 8 + 1 + 1 + 1 + RESERVED_BYTES; }
+
+#[account]
+#[derive(Default)]
+pub struct PredictionBet {
+    pub outcome: Pubkey,
+    pub bettor: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl PredictionBet { pub const SIZE: usize = 32 + 32 + 8 + 1 + 1 + RESERVED_BYTES; }
+
+// Marks a wallet that bet on a match; every join path requires it absent, so a bettor can't
+// enter the match and leave a stake claim_prediction would refuse in the pool
+#[account]
+#[derive(Default)]
+pub struct BetMark {
+    pub match_key: Pubkey,
+    pub bettor: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl BetMark { pub const SIZE: usize = 32 + 32 + 1 + 1 + RESERVED_BYTES; }
+
+// What a prediction pool bets on: the entrant finishing rank 1, or in the top 3.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PredictionKind { Rank1 = 0, Top3 = 1 }
+
+impl PredictionKind {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Rank1),
+            1 => Some(Self::Top3),
+            _ => None,
+        }
+    }
+
+    // Ranks that win, given the match's winners_count
+    pub fn winning_ranks(self, winners_count: u32) -> u32 {
+        match self {
+            Self::Rank1 => 1,
+            Self::Top3 => winners_count.min(3),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+// Per-wallet progress across all tiers. Streaks count consecutive UTC days; a streak is only
+// current while its last_*_day is yesterday or today.
+#[account]
+#[derive(Default)]
+pub struct PlayerProfile {
+    pub player: Pubkey,
+    pub total_matches: u32,
+    pub top1_wins: u32,
+    pub total_prizes: u32,
+    pub prize_lamports: u64, // claimed
+    pub xp: u64,
+    pub level: u32,
+    pub current_match_streak: u32,
+    pub current_win_streak: u32,
+    pub max_win_streak: u32,
+    pub current_top1_streak: u32,
+    pub last_played_day: i64,
+    pub last_prize_day: i64,
+    pub last_top1_day: i64,
+    pub bump: u8,
+    pub referrer: Pubkey, // bound on the first match; default = none
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl PlayerProfile { pub const SIZE: usize = 32 + 4 + 4 + 4 + 8 + 8 + 4 + 4 + 4 + 4 + 4 + 8 + 8 + 8 + 1 + 32 + 1 + RESERVED_BYTES; }
+
+// Referral balance of one wallet. Rebates are credited to this PDA at join and withdrawn
+// with claim_referral.
+#[account]
+#[derive(Default)]
+pub struct ReferralAccount {
+    pub referrer: Pubkey,
+    pub referred_players: u32,
+    pub accrued_lamports: u64, // claimable
+    pub total_earned: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl ReferralAccount { pub const SIZE: usize = 32 + 4 + 8 + 8 + 1 + 1 + RESERVED_BYTES; }
+
+// Authority-defined achievement over one PlayerProfile metric
+#[account]
+#[derive(Default)]
+pub struct Achievement {
+    pub achievement_id: u16,
+    pub metric: u8, // AchievementMetric
+    pub threshold: u64,
+    pub active: bool,
+    pub badge_mint: Pubkey, // default = no badge
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl Achievement { pub const SIZE: usize = 2 + 1 + 8 + 1 + 32 + 1 + 1 + RESERVED_BYTES; }
+
+#[account]
+#[derive(Default)]
+pub struct AchievementUnlock {
+    pub achievement: Pubkey,
+    pub player: Pubkey,
+    pub value: u64, // metric value when unlocked
+    pub unlocked_at: i64,
+    pub badge_minted: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl AchievementUnlock { pub const SIZE: usize = 32 + 32 + 8 + 8 + 1 + 1 + 1 + RESERVED_BYTES; }
+
+// PlayerProfile field an achievement is measured on. Streak metrics use the best/current
+// streak as stored on the profile.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AchievementMetric {
+    TotalMatches = 0,
+    Top1Wins = 1,
+    TotalPrizes = 2,
+    PrizeLamports = 3,
+    Level = 4,
+    MatchStreak = 5,
+    MaxWinStreak = 6,
+    Top1Streak = 7,
+}
+
+impl AchievementMetric {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::TotalMatches),
+            1 => Some(Self::Top1Wins),
+            2 => Some(Self::TotalPrizes),
+            3 => Some(Self::PrizeLamports),
+            4 => Some(Self::Level),
+            5 => Some(Self::MatchStreak),
+            6 => Some(Self::MaxWinStreak),
+            7 => Some(Self::Top1Streak),
+            _ => None,
+        }
+    }
+}
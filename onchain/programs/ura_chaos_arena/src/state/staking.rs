@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+// URACHAOS staking. Staked tokens sit in `stake_vault`; SOL rewards are held in this account
+// and accrue per staked atom (acc_reward_per_share, scaled by REWARD_SCALE).
+#[account]
+#[derive(Default)]
+pub struct StakingPool {
+    pub urac_mint: Pubkey,
+    pub stake_vault: Pubkey,
+    pub total_staked: u64, // excludes tokens cooling down
+    pub acc_reward_per_share: u128,
+    pub revenue_bps: u16, // slice of each match's revenue bucket
+    pub cooldown_secs: u32,
+    pub total_rewards: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl StakingPool { pub const SIZE: usize = 32 + 32 + 8 + 16 + 2 + 4 + 8 + 1 + 1 + RESERVED_BYTES; }
+
+#[account]
+#[derive(Default)]
+pub struct StakePosition {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub reward_debt: u128, // amount x acc_reward_per_share at the last settlement
+    pub pending_rewards: u64,
+    pub unstaking_amount: u64,
+    pub unstake_available_at: i64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl StakePosition { pub const SIZE: usize = 32 + 8 + 16 + 8 + 8 + 8 + 1 + 1 + RESERVED_BYTES; }
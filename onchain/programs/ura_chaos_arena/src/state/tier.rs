@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+// Arena tier: its own ticket price and daily matches
+#[account]
+#[derive(Default)]
+pub struct ArenaTier {
+    pub tier_id: u8,
+    pub ticket_price_usd_micros: u64,
+    pub fixed_lamports_per_ticket: u64, // used only under OraclePolicy::EmergencyFixedPrice
+    pub bump: u8,
+    pub stake_weight_cap: u16, // max ticket multiplier for stake-weighted prizes; 0 = rank only
+    pub allow_reentry: bool,
+    pub max_players: u32, // distinct players per match; 0 = unlimited
+    pub waitlist_enabled: bool,
+    pub join_cutoff_secs: u32, // entries close this long before the day ends
+    pub entry_window_secs: u32, // pre-registration for the next day opens this long before midnight
+    pub max_days_ahead: u8, // join_future horizon; 0 = disabled
+    pub pass_discount_bps: u16,
+    pub guild_prize_bps: u16, // team arena: share of the prize pool for the guild bucket; 0 = solo
+    // Holder-only tiers (see TokenGate): a mint and minimum balance, or an NFT collection
+    pub gate_kind: u8,
+    pub gate_mint: Pubkey,
+    pub gate_min_amount: u64,
+    // Private tournament tiers (see Tournament) charge a fixed entry fee instead of the oracle price
+    pub tournament: bool,
+    pub entry_fee_lamports: u64,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl ArenaTier { pub const SIZE: usize = 1 + 8 + 8 + 1 + 2 + 1 + 4 + 1 + 4 + 4 + 1 + 2 + 2 + 1 + 32 + 8 + 1 + 8 + 1 + RESERVED_BYTES; }
+
+// None: open tier. TokenBalance: hold at least gate_min_amount of gate_mint. VerifiedCollection:
+// hold an NFT whose Metaplex metadata has gate_mint as its verified collection.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TokenGate { None = 0, TokenBalance = 1, VerifiedCollection = 2 }
+
+impl TokenGate {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::None),
+            1 => Some(Self::TokenBalance),
+            2 => Some(Self::VerifiedCollection),
+            _ => None,
+        }
+    }
+}
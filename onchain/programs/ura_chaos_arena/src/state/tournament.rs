@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+
+// Private tournament run on its own tier. Holds the organizer's revenue cut until claimed.
+#[account]
+#[derive(Default)]
+pub struct Tournament {
+    pub organizer: Pubkey,
+    pub tier: Pubkey,
+    pub merkle_root: [u8; 32], // allowlist, see verify_allowlist_proof
+    pub accrued_lamports: u64, // claimable
+    pub total_earned: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl Tournament { pub const SIZE: usize = 32 + 32 + 32 + 8 + 8 + 1 + 1 + RESERVED_BYTES; }
+
+// Proof that `player` passed the tournament's allowlist
+#[account]
+#[derive(Default)]
+pub struct TournamentInvite {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; 32],
+}
+
+impl TournamentInvite { pub const SIZE: usize = 32 + 32 + 1 + 1 + RESERVED_BYTES; }
@@ -0,0 +1,831 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hashv, program::invoke_signed, system_instruction};
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::constants::*;
+use crate::errors::ArenaError;
+use crate::math::*;
+use crate::state::*;
+
+pub(crate) fn unix_day(ts: i64) -> i64 { ts.div_euclid(SECONDS_PER_DAY) }
+
+// Day whose match currently takes entries for `tier`: today, or tomorrow once inside the
+// tier's pre-registration window before midnight.
+pub(crate) fn entry_day(tier: &ArenaTier, now: i64) -> i64 {
+    let today = unix_day(now);
+    let next_start = (today + 1) * SECONDS_PER_DAY;
+    if tier.entry_window_secs > 0 && now >= next_start - tier.entry_window_secs as i64 {
+        today + 1
+    } else {
+        today
+    }
+}
+
+// Checks the `day_id` a join names against the match taking entries now. Within the config's
+// grace window after a rollover the previous entry day is still accepted, so a transaction
+// built just before midnight does not fail. Returns the time the entry counts as made at.
+pub(crate) fn entry_time(config: &ArenaConfig, tier: &ArenaTier, day_id: i64, now: i64) -> Result<i64> {
+    if day_id == entry_day(tier, now) {
+        return Ok(now);
+    }
+    let built_at = now - config.join_grace_secs as i64;
+    require!(day_id == entry_day(tier, built_at), ArenaError::WrongMatchForDay);
+    Ok(built_at)
+}
+
+// Last moment a late join for `day_id` can still land under the grace window; the match
+// cannot be finalized before it.
+pub(crate) fn last_join_at(config: &ArenaConfig, day_id: i64) -> i64 {
+    (day_id + 1) * SECONDS_PER_DAY + config.join_grace_secs as i64
+}
+
+// Entries for `day_id` close join_cutoff_secs before that day ends.
+pub(crate) fn require_entries_open(tier: &ArenaTier, day_id: i64, now: i64) -> Result<()> {
+    let close = (day_id + 1) * SECONDS_PER_DAY - tier.join_cutoff_secs as i64;
+    require!(now < close, ArenaError::JoinClosed);
+    Ok(())
+}
+
+// Waitlist admissions into the `day_id` match follow the same day and cutoff rules as a join,
+// grace window included, so a queue can still be admitted just after midnight.
+pub(crate) fn require_admission_open(config: &ArenaConfig, tier: &ArenaTier, day_id: i64, now: i64) -> Result<()> {
+    let at = entry_time(config, tier, day_id, now)?;
+    require_entries_open(tier, day_id, at)
+}
+
+// Reads a PriceUpdateV2 account and returns its price if it is fully verified, for the
+// configured feed, and published within `max_age` seconds of `now`.
+pub(crate) fn load_price_update(info: &AccountInfo, feed_id: &[u8; 32], now: i64, max_age: u64) -> Result<PriceFeedMessage> {
+    let data = info.try_borrow_data()?;
+    require!(data.len() > 8 && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR, ArenaError::PythError);
+    let update = PriceUpdateV2::deserialize(&mut &data[8..]).map_err(|_| ArenaError::PythError)?;
+    require!(update.verification_level == VerificationLevel::Full, ArenaError::PythUnverified);
+    let price = update.price_message;
+    require!(price.feed_id == *feed_id, ArenaError::PythFeedMismatch);
+    require!(price.publish_time.saturating_add(max_age as i64) >= now, ArenaError::PythStale);
+    Ok(price)
+}
+
+// Accounts every entry path (join, join_exact, join_future) touches
+pub(crate) struct EntryAccounts<'a, 'info> {
+    pub(crate) player: &'a Signer<'info>,
+    pub(crate) tier: &'a Account<'info, ArenaTier>,
+    pub(crate) match_state: &'a mut Account<'info, MatchState>,
+    pub(crate) match_vault: &'a SystemAccount<'info>,
+    pub(crate) entry: &'a mut Account<'info, PlayerEntry>,
+    pub(crate) profile: &'a mut Account<'info, PlayerProfile>,
+    pub(crate) system_program: &'a Program<'info, System>,
+    pub(crate) match_bump: u8,
+    pub(crate) entry_bump: u8,
+    pub(crate) profile_bump: u8,
+    // Season pass redemptions pay from the tier's pass vault (with its bump) instead of the player
+    pub(crate) pass_vault: Option<(&'a SystemAccount<'info>, u8)>,
+    pub(crate) referral: Option<&'a mut Account<'info, ReferralAccount>>,
+    pub(crate) referral_bps: u16,
+    pub(crate) organizer_bps: u16,
+    pub(crate) guild_member: Option<&'a Account<'info, GuildMember>>,
+    pub(crate) guild_standing: Option<&'a mut Account<'info, GuildStanding>>,
+    pub(crate) gate_tokens: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub(crate) gate_metadata: Option<&'a UncheckedAccount<'info>>,
+    pub(crate) gate_nft_mint: Option<&'a InterfaceAccount<'info, Mint>>,
+    pub(crate) invite: Option<&'a Account<'info, TournamentInvite>>,
+    pub(crate) bet_mark: &'a UncheckedAccount<'info>,
+}
+
+// Shared tail of the join paths: opens the `day_id` match on first entry, records the ticket
+// on the player's entry and moves `amount` from the player into the match vault. `ticket` is
+// the price of one ticket, used to weigh the entry in stake-weighted matches. `at` is the
+// time the entry counts as made at for the cutoff (see entry_time); `now` is recorded.
+pub(crate) fn enter_match(mut a: EntryAccounts, day_id: i64, amount: u64, ticket: u64, at: i64, now: i64) -> Result<()> {
+    require_entries_open(a.tier, day_id, at)?;
+    require_no_bets(a.bet_mark)?;
+    let m = a.match_state;
+
+    // Initialize if needed (most fields auto-zero)
+    if m.day_id == 0 {
+        m.day_id = day_id;
+        m.tier = a.tier.key();
+        m.stake_weight_cap = a.tier.stake_weight_cap;
+        m.guild_prize_bps = a.tier.guild_prize_bps;
+        if a.tier.tournament {
+            m.organizer_bps = a.organizer_bps;
+            m.entry_fee_lamports = a.tier.entry_fee_lamports;
+        }
+        m.status = MatchStatus::Open as u8;
+        m.bump = a.match_bump;
+        m.version = ACCOUNT_VERSION;
+    } else {
+        require!(m.day_id == day_id, ArenaError::WrongMatchForDay);
+        require!(m.status == MatchStatus::Open as u8, ArenaError::MatchClosed);
+    }
+
+    // First ticket creates the entry; later ones are re-buys if the tier allows them
+    let entry = a.entry;
+    let first_ticket = entry.tickets == 0;
+    // Re-buys are gated too, so a holder can't sell the token after entering and keep buying
+    require_token_gate(a.tier, &a.player.key(), a.gate_tokens, a.gate_metadata, a.gate_nft_mint)?;
+    if first_ticket {
+        entry.match_key = m.key();
+        entry.player = a.player.key();
+        entry.joined_at = now;
+        entry.bump = a.entry_bump;
+        entry.version = ACCOUNT_VERSION;
+        let max_players = a.tier.max_players;
+        require!(max_players == 0 || m.player_count < max_players, ArenaError::MatchFull);
+        require!(m.waitlist_head == m.waitlist_count, ArenaError::WaitlistPending);
+        require_invited(a.tier, &a.player.key(), a.invite)?;
+        // A referral only binds to a wallet entering its first match
+        if let Some(r) = a.referral.as_deref_mut() {
+            if a.profile.total_matches == 0 && a.profile.referrer == Pubkey::default() && r.referrer != a.player.key() {
+                a.profile.referrer = r.referrer;
+                r.referred_players = r.referred_players.checked_add(1).ok_or(ArenaError::Overflow)?;
+            }
+        }
+        record_match_played(a.profile, a.player.key(), a.profile_bump, day_id, entry_day(a.tier, now))?;
+        if m.guild_prize_bps > 0 {
+            let member = a.guild_member.ok_or(ArenaError::GuildRequired)?;
+            let st = a.guild_standing.as_deref_mut().ok_or(ArenaError::GuildRequired)?;
+            enter_guild(m, entry, member.guild, st)?;
+        }
+    } else {
+        require!(a.tier.allow_reentry, ArenaError::AlreadyJoined);
+    }
+    let weight = stake_weight(amount, ticket, m.stake_weight_cap).map_err(ArenaError::from)?;
+    entry.weight = if m.stake_weight_cap == 0 {
+        weight
+    } else {
+        entry.weight.saturating_add(weight).min(m.stake_weight_cap as u32 * WEIGHT_ONE as u32)
+    };
+    entry.tickets = entry.tickets.checked_add(1).ok_or(ArenaError::Overflow)?;
+    entry.paid = entry.paid.checked_add(amount).ok_or(ArenaError::Overflow)?;
+
+    // Referred players' rebates skip the vault and are credited to the referrer right away;
+    // finalize takes them out of the revenue bucket via referral_lamports.
+    let mut rebate = 0;
+    if a.profile.referrer != Pubkey::default() {
+        let r = a.referral.ok_or(ArenaError::MissingReferral)?;
+        require_keys_eq!(r.referrer, a.profile.referrer, ArenaError::MissingReferral);
+        rebate = referral_rebate(amount, a.referral_bps);
+        if rebate > 0 {
+            pay_entry(a.player, a.pass_vault, a.tier, &r.to_account_info(), a.system_program, rebate)?;
+            credit_referral(r, m, rebate)?;
+        }
+    }
+    fund_vault_rent(a.player, a.match_vault, a.system_program)?;
+    pay_entry(a.player, a.pass_vault, a.tier, &a.match_vault.to_account_info(), a.system_program, amount - rebate)?;
+
+    if first_ticket {
+        m.player_count = m.player_count.checked_add(1).ok_or(ArenaError::Overflow)?;
+    }
+    m.ticket_count = m.ticket_count.checked_add(1).ok_or(ArenaError::Overflow)?;
+    m.pot_lamports = m.pot_lamports.checked_add(amount).ok_or(ArenaError::Overflow)?;
+    Ok(())
+}
+
+// Books a rebate already paid into `r` against the match's revenue bucket.
+pub(crate) fn credit_referral(r: &mut ReferralAccount, m: &mut MatchState, rebate: u64) -> Result<()> {
+    r.accrued_lamports = r.accrued_lamports.checked_add(rebate).ok_or(ArenaError::Overflow)?;
+    r.total_earned = r.total_earned.checked_add(rebate).ok_or(ArenaError::Overflow)?;
+    m.referral_lamports = m.referral_lamports.checked_add(rebate).ok_or(ArenaError::Overflow)?;
+    Ok(())
+}
+
+// Team arenas: tags a first entry with the player's guild and counts it on the guild's standing.
+pub(crate) fn enter_guild(m: &mut MatchState, entry: &mut PlayerEntry, guild: Pubkey, st: &mut GuildStanding) -> Result<()> {
+    require!(st.guild == guild && st.tier == m.tier && st.day_id == m.day_id, ArenaError::GuildRequired);
+    if st.members == 0 {
+        require!(m.guild_count < MAX_GUILDS_PER_MATCH, ArenaError::GuildLimitReached);
+        m.guild_count += 1;
+    }
+    st.members = st.members.checked_add(1).ok_or(ArenaError::Overflow)?;
+    entry.guild = guild;
+    Ok(())
+}
+
+pub(crate) fn init_profile(p: &mut PlayerProfile, player: Pubkey, bump: u8) {
+    if p.player == Pubkey::default() {
+        p.player = player;
+        p.level = 1;
+        p.bump = bump;
+        p.version = ACCOUNT_VERSION;
+    }
+}
+
+// First ticket of a player in a match (any tier). Tickets bought ahead through join_future
+// (day_id after `current_day`, the day taking entries) don't count: the match hasn't been
+// played, and counting it would move last_played_day past today and farm streaks.
+pub(crate) fn record_match_played(p: &mut PlayerProfile, player: Pubkey, bump: u8, day_id: i64, current_day: i64) -> Result<()> {
+    init_profile(p, player, bump);
+    if day_id > current_day {
+        return Ok(());
+    }
+    p.total_matches = p.total_matches.checked_add(1).ok_or(ArenaError::Overflow)?;
+    p.current_match_streak = next_streak(p.current_match_streak, p.last_played_day, day_id);
+    p.last_played_day = p.last_played_day.max(day_id);
+    add_xp(p, XP_PER_MATCH)
+}
+
+// A recorded allocation: every winner earns prize XP, rank 1 also counts as a top-1 win.
+pub(crate) fn record_prize(p: &mut PlayerProfile, player: Pubkey, bump: u8, day_id: i64, rank: u32) -> Result<()> {
+    init_profile(p, player, bump);
+    p.total_prizes = p.total_prizes.checked_add(1).ok_or(ArenaError::Overflow)?;
+    p.current_win_streak = next_streak(p.current_win_streak, p.last_prize_day, day_id);
+    p.max_win_streak = p.max_win_streak.max(p.current_win_streak);
+    p.last_prize_day = p.last_prize_day.max(day_id);
+    let mut xp = XP_PER_PRIZE;
+    if rank == 1 {
+        p.top1_wins = p.top1_wins.checked_add(1).ok_or(ArenaError::Overflow)?;
+        p.current_top1_streak = next_streak(p.current_top1_streak, p.last_top1_day, day_id);
+        p.last_top1_day = p.last_top1_day.max(day_id);
+        xp += XP_PER_TOP1;
+    }
+    add_xp(p, xp)
+}
+
+pub(crate) fn add_xp(p: &mut PlayerProfile, xp: u64) -> Result<()> {
+    p.xp = p.xp.checked_add(xp).ok_or(ArenaError::Overflow)?;
+    p.level = level_for_xp(p.xp);
+    Ok(())
+}
+
+pub(crate) fn profile_metric(p: &PlayerProfile, metric: AchievementMetric) -> u64 {
+    match metric {
+        AchievementMetric::TotalMatches => p.total_matches as u64,
+        AchievementMetric::Top1Wins => p.top1_wins as u64,
+        AchievementMetric::TotalPrizes => p.total_prizes as u64,
+        AchievementMetric::PrizeLamports => p.prize_lamports,
+        AchievementMetric::Level => p.level as u64,
+        AchievementMetric::MatchStreak => p.current_match_streak as u64,
+        AchievementMetric::MaxWinStreak => p.max_win_streak as u64,
+        AchievementMetric::Top1Streak => p.current_top1_streak as u64,
+    }
+}
+
+// Enforces the tier's holder requirement on a new entry.
+pub(crate) fn require_token_gate(
+    tier: &ArenaTier,
+    player: &Pubkey,
+    tokens: Option<&InterfaceAccount<TokenAccount>>,
+    metadata: Option<&UncheckedAccount>,
+    nft_mint: Option<&InterfaceAccount<Mint>>,
+) -> Result<()> {
+    let gate = TokenGate::from_u8(tier.gate_kind).ok_or(ArenaError::InvalidTokenGate)?;
+    if gate == TokenGate::None {
+        return Ok(());
+    }
+    let tokens = tokens.ok_or(ArenaError::TokenGateNotMet)?;
+    require!(tokens.owner == *player, ArenaError::TokenGateNotMet);
+    match gate {
+        TokenGate::TokenBalance => {
+            require!(
+                tokens.mint == tier.gate_mint && tokens.amount >= tier.gate_min_amount,
+                ArenaError::TokenGateNotMet
+            );
+        }
+        TokenGate::VerifiedCollection => {
+            let nft_mint = nft_mint.ok_or(ArenaError::TokenGateNotMet)?;
+            require_keys_eq!(nft_mint.key(), tokens.mint, ArenaError::TokenGateNotMet);
+            require!(holds_single_nft(nft_mint, tokens.amount), ArenaError::TokenGateNotMet);
+            let metadata = metadata.ok_or(ArenaError::TokenGateNotMet)?;
+            let (expected, _) = Pubkey::find_program_address(
+                &[b"metadata", mpl_token_metadata::ID.as_ref(), tokens.mint.as_ref()],
+                &mpl_token_metadata::ID,
+            );
+            require_keys_eq!(metadata.key(), expected, ArenaError::TokenGateNotMet);
+            require_keys_eq!(*metadata.owner, mpl_token_metadata::ID, ArenaError::TokenGateNotMet);
+            let data = metadata.try_borrow_data()?;
+            let meta = MetadataPrefix::deserialize(&mut &data[..]).map_err(|_| ArenaError::TokenGateNotMet)?;
+            require!(nft_in_collection(&meta, &tokens.mint, &tier.gate_mint), ArenaError::TokenGateNotMet);
+        }
+        TokenGate::None => {}
+    }
+    Ok(())
+}
+
+// Fungible tokens can carry collection metadata too; only a 0-decimal mint with a supply of
+// one, held in full, is the NFT itself.
+pub(crate) fn holds_single_nft(mint: &spl_token_2022::state::Mint, amount: u64) -> bool {
+    mint.decimals == 0 && mint.supply == 1 && amount == 1
+}
+
+pub(crate) fn nft_in_collection(meta: &MetadataPrefix, nft_mint: &Pubkey, collection: &Pubkey) -> bool {
+    meta.mint == *nft_mint
+        && meta.collection.as_ref().is_some_and(|c| c.verified && c.key == *collection)
+}
+
+// A wallet is either a player or a bettor in a match. place_bet needs the wallet's entry and
+// waitlist addresses empty; the join paths need its BetMark empty.
+pub(crate) fn require_spectator(entry: &AccountInfo, waitlist: &AccountInfo) -> Result<()> {
+    require!(entry.data_is_empty() && waitlist.data_is_empty(), ArenaError::PlayersCannotBet);
+    Ok(())
+}
+
+pub(crate) fn require_no_bets(bet_mark: &AccountInfo) -> Result<()> {
+    require!(bet_mark.data_is_empty(), ArenaError::BettorsCannotJoin);
+    Ok(())
+}
+
+pub(crate) fn require_not_paused(config: &ArenaConfig, flag: u8) -> Result<()> {
+    require!(config.paused_flags & flag == 0, ArenaError::Paused);
+    Ok(())
+}
+
+// Tournament tiers only admit wallets holding an invite for that tournament.
+pub(crate) fn require_invited(tier: &Account<ArenaTier>, player: &Pubkey, invite: Option<&Account<TournamentInvite>>) -> Result<()> {
+    if !tier.tournament {
+        return Ok(());
+    }
+    let invite = invite.ok_or(ArenaError::NotInvited)?;
+    let (tournament, _) = Pubkey::find_program_address(&[b"tournament", tier.key().as_ref()], &crate::ID);
+    require!(invite.tournament == tournament && invite.player == *player, ArenaError::NotInvited);
+    Ok(())
+}
+
+// Allowlist trees hash leaves as sha256(0x00 || wallet) and nodes as sha256(0x01 || a || b)
+// with the pair sorted, so proofs carry no left/right flags.
+pub(crate) fn verify_allowlist_proof(proof: &[[u8; 32]], root: &[u8; 32], player: &Pubkey) -> bool {
+    let mut node = hashv(&[&[0u8], player.as_ref()]).to_bytes();
+    for sibling in proof {
+        let (a, b) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        node = hashv(&[&[1u8], &a, &b]).to_bytes();
+    }
+    node == *root
+}
+
+// Transfers entry lamports from the player, or from the tier's pass vault for redemptions.
+// Lamports a data-less vault PDA still needs to be rent-exempt. The system program rejects a
+// first transfer below the minimum, so the payer opening a vault tops it up first; the
+// reserve stays in the vault and payouts only move the lamports booked on top of it.
+pub(crate) fn vault_rent_shortfall(vault_lamports: u64, rent_exempt: u64) -> u64 {
+    rent_exempt.saturating_sub(vault_lamports)
+}
+
+pub(crate) fn fund_vault_rent<'info>(
+    payer: &Signer<'info>,
+    vault: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let missing = vault_rent_shortfall(vault.lamports(), Rent::get()?.minimum_balance(0));
+    if missing == 0 {
+        return Ok(());
+    }
+    let ix = system_instruction::transfer(&payer.key(), &vault.key(), missing);
+    invoke_signed(
+        &ix,
+        &[payer.to_account_info(), vault.to_account_info(), system_program.to_account_info()],
+        &[],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn pay_entry<'info>(
+    player: &Signer<'info>,
+    pass_vault: Option<(&SystemAccount<'info>, u8)>,
+    tier: &Account<'info, ArenaTier>,
+    to: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    lamports: u64,
+) -> Result<()> {
+    let tier_key = tier.key();
+    let (from, bump) = match pass_vault {
+        Some((v, bump)) => (v.to_account_info(), Some(bump)),
+        None => (player.to_account_info(), None),
+    };
+    let ix = system_instruction::transfer(from.key, to.key, lamports);
+    let infos = [from.clone(), to.clone(), system_program.to_account_info()];
+    match bump {
+        Some(bump) => invoke_signed(&ix, &infos, &[&[b"pass_vault", tier_key.as_ref(), &[bump]]])?,
+        None => invoke_signed(&ix, &infos, &[])?,
+    }
+    Ok(())
+}
+
+// Moves the position's accrual since its last settlement into pending_rewards.
+pub(crate) fn settle_stake_rewards(pos: &mut StakePosition, acc_reward_per_share: u128) -> Result<()> {
+    let accrued = reward_debt(pos.amount, acc_reward_per_share).map_err(ArenaError::from)?;
+    let reward = accrued.checked_sub(pos.reward_debt).ok_or(ArenaError::Overflow)? / REWARD_SCALE;
+    pos.pending_rewards = pos.pending_rewards.checked_add(reward as u64).ok_or(ArenaError::Overflow)?;
+    pos.reward_debt = accrued;
+    Ok(())
+}
+
+// Lamports required for one ticket of `tier` under the configured oracle policy. Tournament
+// matches keep the fee they opened with, so update_tournament only reprices later matches.
+pub(crate) fn ticket_lamports(
+    config: &ArenaConfig,
+    tier: &ArenaTier,
+    m: Option<&MatchState>,
+    price_update: Option<&UncheckedAccount>,
+    now: i64,
+) -> Result<u64> {
+    if tier.tournament {
+        let opened_at = m.map_or(0, |m| m.entry_fee_lamports);
+        return Ok(if opened_at > 0 { opened_at } else { tier.entry_fee_lamports });
+    }
+    let policy = OraclePolicy::from_u8(config.oracle_policy).ok_or(ArenaError::InvalidOraclePolicy)?;
+    if policy == OraclePolicy::EmergencyFixedPrice {
+        require!(tier.fixed_lamports_per_ticket > 0, ArenaError::NoFixedTicketPrice);
+        return Ok(tier.fixed_lamports_per_ticket);
+    }
+
+    require!(config.pyth_feed_id != [0; 32], ArenaError::PythFeedUnset);
+    let info = price_update.ok_or(ArenaError::MissingPriceUpdate)?;
+    let price = load_price_update(info, &config.pyth_feed_id, now, config.pyth_max_age_secs)?;
+    let abs_price = price.price.unsigned_abs() as u128;
+    let conf_ok = price.conf as u128 * BPS_100 <= abs_price * config.pyth_max_conf_bps as u128;
+    let quote = if conf_ok {
+        price.price
+    } else {
+        require!(policy == OraclePolicy::Lenient, ArenaError::PythConfTooWide);
+        let conf = i64::try_from(price.conf).map_err(|_| ArenaError::PythConfTooWide)?;
+        price.price.saturating_sub(conf)
+    };
+    Ok(lamports_for_usd_micros_ceil(tier.ticket_price_usd_micros, quote, price.exponent).map_err(ArenaError::from)?)
+}
+
+// Moves lamports out of an account owned by this program (e.g. a waitlist escrow).
+pub(crate) fn move_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from.lamports().checked_sub(lamports).ok_or(ArenaError::Overflow)?;
+    **to.try_borrow_mut_lamports()? = to.lamports().checked_add(lamports).ok_or(ArenaError::Overflow)?;
+    Ok(())
+}
+
+pub(crate) fn transfer_from_vault<'info>(
+    from: &SystemAccount<'info>,
+    to: &impl ToAccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    lamports: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if lamports == 0 { return Ok(()); }
+    let ix = system_instruction::transfer(&from.key(), &to.to_account_info().key(), lamports);
+    invoke_signed(
+        &ix,
+        &[
+            from.to_account_info(),
+            to.to_account_info(),
+            system_program.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+// Creates the PDA `account` with `space` bytes owned by `owner`. Anyone can send lamports to
+// the address beforehand, which makes create_account fail; as Anchor's init does, a funded
+// address is topped up to rent exemption, then allocated and assigned instead.
+pub(crate) fn create_pda_account<'info>(
+    payer: &Signer<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let (payer_info, system_info) = (payer.to_account_info(), system_program.to_account_info());
+    let funded = account.lamports();
+    if funded == 0 {
+        let ix = system_instruction::create_account(&payer.key(), account.key, rent, space as u64, owner);
+        invoke_signed(&ix, &[payer_info, account.clone(), system_info], &[signer_seeds])?;
+        return Ok(());
+    }
+    if funded < rent {
+        let ix = system_instruction::transfer(&payer.key(), account.key, rent - funded);
+        invoke_signed(&ix, &[payer_info, account.clone(), system_info.clone()], &[])?;
+    }
+    let ix = system_instruction::allocate(account.key, space as u64);
+    invoke_signed(&ix, &[account.clone(), system_info.clone()], &[signer_seeds])?;
+    let ix = system_instruction::assign(account.key, owner);
+    invoke_signed(&ix, &[account.clone(), system_info], &[signer_seeds])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_window_and_cutoff() {
+        let tier = ArenaTier { join_cutoff_secs: 12 * 3_600, entry_window_secs: 6 * 3_600, ..Default::default() };
+        let day = 20_000;
+        let start = day * SECONDS_PER_DAY;
+        // Morning: today's match, open
+        assert_eq!(entry_day(&tier, start + 3_600), day);
+        assert!(require_entries_open(&tier, day, start + 3_600).is_ok());
+        // Afternoon: still today's match, but past the cutoff
+        assert_eq!(entry_day(&tier, start + 13 * 3_600), day);
+        assert!(require_entries_open(&tier, day, start + 13 * 3_600).is_err());
+        // Evening: pre-registration for tomorrow
+        assert_eq!(entry_day(&tier, start + 19 * 3_600), day + 1);
+        assert!(require_entries_open(&tier, day + 1, start + 19 * 3_600).is_ok());
+    }
+
+    #[test]
+    fn join_day_grace_window() {
+        let tier = ArenaTier::default();
+        let mut config = ArenaConfig::default();
+        let day = 20_000;
+        let midnight = (day + 1) * SECONDS_PER_DAY;
+        assert_eq!(entry_time(&config, &tier, day, midnight - 1).unwrap(), midnight - 1);
+        // Built at 23:59:59, landed at 00:00:02
+        assert!(entry_time(&config, &tier, day, midnight + 2).is_err());
+        config.join_grace_secs = 30;
+        let at = entry_time(&config, &tier, day, midnight + 2).unwrap();
+        assert!(require_entries_open(&tier, day, at).is_ok());
+        // Finalizing waits out the same window
+        assert_eq!(last_join_at(&config, day), midnight + 30);
+        assert!(entry_time(&config, &tier, day, midnight + 31).is_err());
+        // Today's match and far-off days
+        assert_eq!(entry_time(&config, &tier, day + 1, midnight + 2).unwrap(), midnight + 2);
+        assert!(entry_time(&config, &tier, day + 2, midnight + 2).is_err());
+    }
+
+    #[test]
+    fn waitlist_admission_at_midnight() {
+        let tier = ArenaTier::default();
+        let config = ArenaConfig { join_grace_secs: 30, ..Default::default() };
+        let day = 20_000;
+        let midnight = (day + 1) * SECONDS_PER_DAY;
+        assert!(require_admission_open(&config, &tier, day, midnight - 1).is_ok());
+        // Yesterday's match still takes joins inside the grace window, so admissions too
+        assert!(require_admission_open(&config, &tier, day, midnight + 2).is_ok());
+        assert!(require_admission_open(&config, &tier, day, midnight + 31).is_err());
+        assert!(require_admission_open(&ArenaConfig::default(), &tier, day, midnight + 2).is_err());
+        // A tier's cutoff closes admissions as it closes joins
+        let cutoff = ArenaTier { join_cutoff_secs: 3_600, ..Default::default() };
+        assert!(require_admission_open(&config, &cutoff, day, midnight - 3_600).is_err());
+    }
+
+    #[test]
+    fn staking_rewards_accrue_pro_rata() {
+        let mut a = StakePosition { amount: 3_000, ..Default::default() };
+        let mut b = StakePosition { amount: 1_000, ..Default::default() };
+        // 1 SOL of revenue over 4_000 staked atoms
+        let acc = 1_000_000_000u128 * REWARD_SCALE / 4_000;
+        settle_stake_rewards(&mut a, acc).unwrap();
+        settle_stake_rewards(&mut b, acc).unwrap();
+        assert_eq!((a.pending_rewards, b.pending_rewards), (750_000_000, 250_000_000));
+        // Settling again without new revenue adds nothing
+        settle_stake_rewards(&mut a, acc).unwrap();
+        assert_eq!(a.pending_rewards, 750_000_000);
+    }
+
+    #[test]
+    fn profile_streaks() {
+        let mut p = PlayerProfile::default();
+        let player = Pubkey::new_unique();
+        record_match_played(&mut p, player, 7, 100, 100).unwrap();
+        record_match_played(&mut p, player, 7, 100, 100).unwrap(); // second tier, same day
+        record_match_played(&mut p, player, 7, 101, 101).unwrap();
+        assert_eq!((p.total_matches, p.current_match_streak, p.level), (3, 2, 1));
+        record_match_played(&mut p, player, 7, 103, 103).unwrap();
+        assert_eq!(p.current_match_streak, 1);
+
+        record_prize(&mut p, player, 7, 101, 1).unwrap();
+        record_prize(&mut p, player, 7, 102, 2).unwrap();
+        record_prize(&mut p, player, 7, 103, 1).unwrap();
+        assert_eq!((p.total_prizes, p.current_win_streak, p.max_win_streak), (3, 3, 3));
+        assert_eq!((p.top1_wins, p.current_top1_streak), (2, 1));
+        assert_eq!(p.xp, 4 * XP_PER_MATCH + 3 * XP_PER_PRIZE + 2 * XP_PER_TOP1);
+        assert_eq!(p.level, level_for_xp(p.xp));
+        assert_eq!((p.player, p.bump), (player, 7));
+    }
+
+    #[test]
+    fn future_tickets_do_not_count_as_played() {
+        let mut p = PlayerProfile::default();
+        let player = Pubkey::new_unique();
+        record_match_played(&mut p, player, 7, 100, 100).unwrap();
+        // join_future for the next week: profile created, nothing counted yet
+        for day in 101..=107 {
+            record_match_played(&mut p, player, 7, day, 100).unwrap();
+        }
+        assert_eq!((p.total_matches, p.current_match_streak, p.last_played_day), (1, 1, 100));
+        assert_eq!(p.xp, XP_PER_MATCH);
+        // Playing the next day still extends the streak
+        record_match_played(&mut p, player, 7, 101, 101).unwrap();
+        assert_eq!((p.total_matches, p.current_match_streak), (2, 2));
+
+        let mut fresh = PlayerProfile::default();
+        record_match_played(&mut fresh, player, 7, 105, 100).unwrap();
+        assert_eq!((fresh.player, fresh.total_matches, fresh.xp), (player, 0, 0));
+    }
+
+    #[test]
+    fn team_arena_entries() {
+        let tier = Pubkey::new_unique();
+        let mut m = MatchState { tier, day_id: 20_000, guild_prize_bps: 1_000, ..Default::default() };
+        let guild = Pubkey::new_unique();
+        let mut st = GuildStanding { tier, day_id: 20_000, guild, ..Default::default() };
+        let mut entry = PlayerEntry::default();
+        enter_guild(&mut m, &mut entry, guild, &mut st).unwrap();
+        enter_guild(&mut m, &mut PlayerEntry::default(), guild, &mut st).unwrap();
+        assert_eq!((entry.guild, st.members, m.guild_count), (guild, 2, 1));
+        // Another guild's standing, or one for another day, does not count
+        assert!(enter_guild(&mut m, &mut PlayerEntry::default(), Pubkey::new_unique(), &mut st).is_err());
+        let mut stale = GuildStanding { day_id: 19_999, ..st.clone() };
+        assert!(enter_guild(&mut m, &mut PlayerEntry::default(), guild, &mut stale).is_err());
+        // A new guild past the per-match limit is turned away
+        m.guild_count = MAX_GUILDS_PER_MATCH;
+        let other = Pubkey::new_unique();
+        let mut fresh = GuildStanding { tier, day_id: 20_000, guild: other, ..Default::default() };
+        assert!(enter_guild(&mut m, &mut PlayerEntry::default(), other, &mut fresh).is_err());
+    }
+
+    #[test]
+    fn tournament_matches_keep_their_fee() {
+        let config = ArenaConfig::default();
+        let tier = ArenaTier { tournament: true, entry_fee_lamports: 2_000, ..Default::default() };
+        let mut m = MatchState::default();
+        // Not opened yet (or opened before the fee was copied): the tier's current fee
+        assert_eq!(ticket_lamports(&config, &tier, Some(&m), None, 0).unwrap(), 2_000);
+        assert_eq!(ticket_lamports(&config, &tier, None, None, 0).unwrap(), 2_000);
+        // Opened at the old fee: a reprice does not reach it
+        m.entry_fee_lamports = 1_000;
+        assert_eq!(ticket_lamports(&config, &tier, Some(&m), None, 0).unwrap(), 1_000);
+    }
+
+    // A PriceUpdateV2 account as the receiver program writes it. ticket_lamports takes the
+    // account by reference for the program's lifetime, so the fixture is leaked.
+    fn price_update_fixture(feed_id: [u8; 32], level: VerificationLevel, price: i64, conf: u64, publish_time: i64) -> &'static AccountInfo<'static> {
+        let update = PriceUpdateV2 {
+            write_authority: Pubkey::new_unique(),
+            verification_level: level,
+            price_message: PriceFeedMessage {
+                feed_id,
+                price,
+                conf,
+                exponent: -8,
+                publish_time,
+                prev_publish_time: publish_time - 1,
+                ema_price: price,
+                ema_conf: conf,
+            },
+            posted_slot: 1,
+        };
+        let mut data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+        update.serialize(&mut data).unwrap();
+        let key = Box::leak(Box::new(Pubkey::new_unique()));
+        let lamports = Box::leak(Box::new(1_000_000u64));
+        Box::leak(Box::new(AccountInfo::new(key, false, false, lamports, data.leak(), &pyth_receiver::ID, false, 0)))
+    }
+
+    #[test]
+    fn price_update_decoding() {
+        let feed = [7u8; 32];
+        let now = 1_000_000;
+        // Pin the hand-decoded layout to the receiver's: discriminator, then the feed id after
+        // write_authority and a one-byte Full verification level
+        assert_eq!(PRICE_UPDATE_V2_DISCRIMINATOR, hashv(&[b"account:PriceUpdateV2"]).to_bytes()[..8]);
+        let full = price_update_fixture(feed, VerificationLevel::Full, 150_00000000, 0, now - 10);
+        assert_eq!(full.data_len(), 8 + 32 + 1 + 84 + 8);
+        assert_eq!(full.try_borrow_data().unwrap()[41..73], feed);
+
+        let price = load_price_update(full, &feed, now, 60).unwrap();
+        assert_eq!((price.price, price.exponent, price.publish_time), (150_00000000, -8, now - 10));
+        // Exactly max_age old is still fresh
+        assert!(load_price_update(full, &feed, now, 10).is_ok());
+        assert_eq!(load_price_update(full, &feed, now, 9).err(), Some(ArenaError::PythStale.into()));
+        assert_eq!(load_price_update(full, &[8; 32], now, 60).err(), Some(ArenaError::PythFeedMismatch.into()));
+
+        let partial = price_update_fixture(feed, VerificationLevel::Partial { num_signatures: 5 }, 150_00000000, 0, now);
+        assert_eq!(load_price_update(partial, &feed, now, 60).err(), Some(ArenaError::PythUnverified.into()));
+
+        let foreign = price_update_fixture(feed, VerificationLevel::Full, 150_00000000, 0, now);
+        foreign.try_borrow_mut_data().unwrap()[0] ^= 1;
+        assert_eq!(load_price_update(foreign, &feed, now, 60).err(), Some(ArenaError::PythError.into()));
+        let (key, mut lamports) = (Pubkey::new_unique(), 1u64);
+        let mut short = full.try_borrow_data().unwrap()[..60].to_vec();
+        let truncated = AccountInfo::new(&key, false, false, &mut lamports, &mut short, &pyth_receiver::ID, false, 0);
+        assert_eq!(load_price_update(&truncated, &feed, now, 60).err(), Some(ArenaError::PythError.into()));
+    }
+
+    #[test]
+    fn ticket_price_from_oracle() {
+        let feed = [7u8; 32];
+        let now = 1_000_000;
+        let mut config = ArenaConfig {
+            pyth_feed_id: feed,
+            pyth_max_age_secs: 60,
+            pyth_max_conf_bps: 100,
+            oracle_policy: OraclePolicy::Strict as u8,
+            ..Default::default()
+        };
+        // $1.00 ticket at $100/SOL
+        let tier = ArenaTier { ticket_price_usd_micros: 1_000_000, ..Default::default() };
+        let quote = |info: &'static AccountInfo<'static>, config: &ArenaConfig| {
+            ticket_lamports(config, &tier, None, Some(&UncheckedAccount::try_from(info)), now)
+        };
+
+        let good = price_update_fixture(feed, VerificationLevel::Full, 100_00000000, 1_00000000, now);
+        assert_eq!(quote(good, &config).unwrap(), 10_000_000);
+        assert_eq!(ticket_lamports(&config, &tier, None, None, now).err(), Some(ArenaError::MissingPriceUpdate.into()));
+
+        let negative = price_update_fixture(feed, VerificationLevel::Full, -100_00000000, 0, now);
+        assert_eq!(quote(negative, &config).err(), Some(ArenaError::PythError.into()));
+        let zero = price_update_fixture(feed, VerificationLevel::Full, 0, 0, now);
+        assert_eq!(quote(zero, &config).err(), Some(ArenaError::PythError.into()));
+
+        // Confidence of 2% against a 1% limit: Strict refuses, Lenient prices at price - conf
+        let wide = price_update_fixture(feed, VerificationLevel::Full, 100_00000000, 2_00000000, now);
+        assert_eq!(quote(wide, &config).err(), Some(ArenaError::PythConfTooWide.into()));
+        config.oracle_policy = OraclePolicy::Lenient as u8;
+        assert_eq!(quote(wide, &config).unwrap(), 10_204_082);
+
+        // A baseline config migrated without a feed can't price tickets until set_pyth_feed
+        config.pyth_feed_id = [0; 32];
+        assert_eq!(quote(good, &config).err(), Some(ArenaError::PythFeedUnset.into()));
+    }
+
+    // Metaplex pads name/symbol/uri with NULs to fixed widths and zero-fills the account past
+    // the fields we read; the gate only parses the prefix, as on-chain.
+    fn metadata_fixture(mint: Pubkey, collection: Option<MetadataCollection>) -> MetadataPrefix {
+        let pad = |s: &str, n: usize| format!("{s:\0<n$}");
+        let meta = MetadataPrefix {
+            key: 4,
+            update_authority: Pubkey::new_unique(),
+            mint,
+            name: pad("Chaos #1", 32),
+            symbol: pad("URA", 10),
+            uri: pad("https://example.com/1.json", 200),
+            seller_fee_basis_points: 500,
+            creators: Some(vec![
+                MetadataCreator { address: Pubkey::new_unique(), verified: true, share: 60 },
+                MetadataCreator { address: Pubkey::new_unique(), verified: false, share: 40 },
+            ]),
+            primary_sale_happened: true,
+            is_mutable: true,
+            edition_nonce: Some(254),
+            token_standard: Some(0),
+            collection,
+        };
+        let mut data = meta.try_to_vec().unwrap();
+        data.resize(679, 0);
+        MetadataPrefix::deserialize(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn collection_gate_metadata() {
+        let (nft, collection) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let verified = metadata_fixture(nft, Some(MetadataCollection { verified: true, key: collection }));
+        assert_eq!(verified.name.len(), 32);
+        assert_eq!(verified.creators.as_ref().map(Vec::len), Some(2));
+        assert!(nft_in_collection(&verified, &nft, &collection));
+        // Metadata for another NFT, or another collection
+        assert!(!nft_in_collection(&verified, &Pubkey::new_unique(), &collection));
+        assert!(!nft_in_collection(&verified, &nft, &Pubkey::new_unique()));
+        // Claims the collection but the collection authority never verified it
+        let unverified = metadata_fixture(nft, Some(MetadataCollection { verified: false, key: collection }));
+        assert!(!nft_in_collection(&unverified, &nft, &collection));
+        assert!(!nft_in_collection(&metadata_fixture(nft, None), &nft, &collection));
+    }
+
+    #[test]
+    fn collection_gate_needs_the_nft() {
+        let nft = spl_token_2022::state::Mint { decimals: 0, supply: 1, is_initialized: true, ..Default::default() };
+        assert!(holds_single_nft(&nft, 1));
+        assert!(!holds_single_nft(&nft, 0));
+        // A fungible or multi-edition mint registered under the collection
+        assert!(!holds_single_nft(&spl_token_2022::state::Mint { decimals: 6, ..nft }, 1));
+        assert!(!holds_single_nft(&spl_token_2022::state::Mint { supply: 1_000, ..nft }, 1));
+        assert!(!holds_single_nft(&spl_token_2022::state::Mint { supply: 1_000, ..nft }, 2));
+    }
+
+    #[test]
+    fn allowlist_proofs() {
+        let leaf = |k: &Pubkey| hashv(&[&[0u8], k.as_ref()]).to_bytes();
+        let node = |a: [u8; 32], b: [u8; 32]| {
+            let (a, b) = if a <= b { (a, b) } else { (b, a) };
+            hashv(&[&[1u8], &a, &b]).to_bytes()
+        };
+        let (p0, p1, p2) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let left = node(leaf(&p0), leaf(&p1));
+        let root = node(left, leaf(&p2));
+
+        assert!(verify_allowlist_proof(&[leaf(&p1), leaf(&p2)], &root, &p0));
+        assert!(verify_allowlist_proof(&[leaf(&p0), leaf(&p2)], &root, &p1));
+        assert!(verify_allowlist_proof(&[left], &root, &p2));
+        assert!(!verify_allowlist_proof(&[left], &root, &Pubkey::new_unique()));
+        assert!(!verify_allowlist_proof(&[leaf(&p2)], &root, &p0));
+        // A single-wallet list: the root is the leaf itself
+        assert!(verify_allowlist_proof(&[], &leaf(&p0), &p0));
+    }
+
+    #[test]
+    fn bettors_and_players_stay_apart() {
+        let (key, owner) = (Pubkey::new_unique(), crate::ID);
+        let (mut l0, mut l1) = (0u64, 1u64);
+        let (mut none, mut some) = (vec![], vec![1u8; 8]);
+        let empty = AccountInfo::new(&key, false, false, &mut l0, &mut none, &owner, false, 0);
+        let existing = AccountInfo::new(&key, false, false, &mut l1, &mut some, &owner, false, 0);
+        // place_bet: a wallet with an entry or a waitlist spot is a player
+        assert!(require_spectator(&empty, &empty).is_ok());
+        assert!(require_spectator(&existing, &empty).is_err());
+        assert!(require_spectator(&empty, &existing).is_err());
+        // Joins and join_waitlist: a wallet that bet is a bettor
+        assert!(require_no_bets(&empty).is_ok());
+        assert!(require_no_bets(&existing).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! Parses the program source the same way `anchor idl parse` does and checks that the
+//! checked-in client IDL (and every instruction the frontend and keeper call) matches it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn repo_root() -> PathBuf {
+    manifest_dir().join("../../..")
+}
+
+fn generated_idl() -> Value {
+    let idl = anchor_syn::idl::parse::file::parse(
+        manifest_dir().join("src/lib.rs"),
+        env!("CARGO_PKG_VERSION").to_string(),
+        false,
+        true,
+        true,
+    )
+    .expect("program source should parse into an IDL");
+    serde_json::to_value(idl).unwrap()
+}
+
+fn client_idl() -> Value {
+    let path = repo_root().join("src/chain/idl/ura_chaos_arena.json");
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn instruction<'a>(idl: &'a Value, name: &str) -> Option<&'a Value> {
+    idl["instructions"].as_array()?.iter().find(|ix| ix["name"] == name)
+}
+
+fn account_summary(ix: &Value) -> Vec<(String, bool, bool, bool)> {
+    ix["accounts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| {
+            (
+                a["name"].as_str().unwrap().to_string(),
+                a["isMut"].as_bool().unwrap(),
+                a["isSigner"].as_bool().unwrap(),
+                a["isOptional"].as_bool().unwrap_or(false),
+            )
+        })
+        .collect()
+}
+
+// Collects `program.methods.<name>(` call sites, including commented-out keeper outlines.
+fn called_methods(path: &Path) -> Vec<String> {
+    let src = fs::read_to_string(path).unwrap();
+    let mut names = Vec::new();
+    for part in src.split("program.methods").skip(1) {
+        let name: String = part
+            .trim_start()
+            .trim_start_matches('.')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+#[test]
+fn client_idl_matches_program() {
+    let generated = generated_idl();
+    let client = client_idl();
+    for ix in client["instructions"].as_array().unwrap() {
+        let name = ix["name"].as_str().unwrap();
+        let program_ix = instruction(&generated, name)
+            .unwrap_or_else(|| panic!("client IDL instruction `{name}` is not in the program"));
+        assert_eq!(account_summary(ix), account_summary(program_ix), "accounts of `{name}`");
+        assert_eq!(ix["args"], program_ix["args"], "args of `{name}`");
+    }
+}
+
+#[test]
+fn idl_covers_frontend_and_keeper_calls() {
+    let generated = generated_idl();
+    let client = client_idl();
+    let callers = [
+        repo_root().join("src/chain/arena.ts"),
+        repo_root().join("scripts/keeper.ts"),
+    ];
+    for path in &callers {
+        let methods = called_methods(path);
+        assert!(!methods.is_empty(), "no program.methods calls found in {}", path.display());
+        for name in methods {
+            assert!(instruction(&generated, &name).is_some(), "`{name}` ({}) is not in the program", path.display());
+            assert!(instruction(&client, &name).is_some(), "`{name}` ({}) is not in the client IDL", path.display());
+        }
+    }
+}
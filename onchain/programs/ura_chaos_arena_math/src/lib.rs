@@ -0,0 +1,416 @@
+// Payout, pricing and progression math shared by the program and off-chain simulations.
+// A `no_std` crate with no dependencies (no Anchor, no allocation), so an off-chain crate can
+// depend on it directly and reproduce on-chain amounts exactly. The program re-exports it as
+// `ura_chaos_arena::math` and maps MathError onto ArenaError.
+#![cfg_attr(not(test), no_std)]
+
+pub const PCT_5: u128 = 5;
+pub const PCT_15: u128 = 15;
+pub const PCT_33: u128 = 33;
+pub const PCT_100: u128 = 100;
+pub const BPS_100: u128 = 10_000;
+pub const LAMPORTS_PER_SOL_CONST: u128 = 1_000_000_000;
+// Ticket prices are USD micro-units ($1 = 1_000_000)
+pub const USD_MICROS: u128 = 1_000_000;
+// Pyth exponents seen in practice are around -8; anything outside this is rejected
+pub const PYTH_EXPO_ABS_MAX: u32 = 18;
+// Stake weighting: entry weights are in 1/10_000ths of a minimum ticket
+pub const WEIGHT_ONE: u64 = 10_000;
+// Rank shares for stake-weighted payouts, in parts per 1e12 of the prize pool
+pub const SHARE_SCALE: u128 = 1_000_000_000_000;
+// Prize pool split: rank 1, group 2 and group 3 (see winner_groups)
+pub const RANK1_SHARE_PCT: u128 = 50;
+pub const GROUP2_SHARE_PCT: u128 = 35;
+pub const GROUP3_SHARE_PCT: u128 = 15;
+// Guild bucket split among the top three guilds
+pub const GUILD_RANK_PCT: [u64; 3] = [50, 30, 20];
+// Input limits the program enforces and this module is tested up to (re-exported by the
+// program's constants): ticket price in USD micro-units, stake multiplier, prediction fee
+pub const TICKET_USD_MICROS_MAX: u64 = 10_000 * USD_MICROS as u64;
+pub const STAKE_MULTIPLIER_MAX: u16 = 100;
+pub const PREDICTION_FEE_BPS_MAX: u16 = 1_000;
+// Levels follow src/utils/playerProgress.ts: 500 XP for level 1, then floor(prev * 1.2).
+pub const XP_LEVEL_BASE: u64 = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    Overflow,
+    InvalidRank,
+    InvalidAmount,
+    InvalidPrice,
+    ZeroAllocation,
+}
+
+pub type MathResult<T> = core::result::Result<T, MathError>;
+
+pub fn ceil_div(numer: u64, denom: u64) -> u64 { numer.div_ceil(denom) }
+
+// Winners (top third of distinct players) and how they split into rank 1, group 2 (the next
+// ~15%) and group 3 (the rest).
+pub fn winner_groups(players: u32) -> (u32, u32, u32) {
+    let total = players.max(1) as u64;
+    let winners_count = ceil_div(total * PCT_33 as u64, PCT_100 as u64) as u32;
+    let group2_count = (ceil_div(total * PCT_15 as u64, PCT_100 as u64) as i64 - 1).max(0) as u32;
+    let group2_count = group2_count.min(winners_count.saturating_sub(1));
+    let group3_count = winners_count.saturating_sub(1 + group2_count);
+    (winners_count, group2_count, group3_count)
+}
+
+pub fn compute_remainder_for_top1(prize: u64, winners: u32, group2: u32, group3: u32) -> u64 {
+    if winners == 0 { return 0; }
+    let top1 = (prize as u128 * RANK1_SHARE_PCT) / PCT_100;
+    let g2_total = (prize as u128 * GROUP2_SHARE_PCT) / PCT_100;
+    let g3_total = (prize as u128 * GROUP3_SHARE_PCT) / PCT_100;
+
+    let g2_each = if group2 > 0 { g2_total / group2 as u128 } else { 0 };
+    let g3_each = if group3 > 0 { g3_total / group3 as u128 } else { 0 };
+
+    // Sum of floors
+    let sum = top1 + g2_each * group2 as u128 + g3_each * group3 as u128;
+    let prize_u128 = prize as u128;
+    let rem = prize_u128.saturating_sub(sum);
+    rem as u64
+}
+
+pub fn compute_rank_allocation(
+    prize: u64,
+    winners: u32,
+    group2: u32,
+    group3: u32,
+    rank: u32,
+    remainder_for_top1: u64,
+) -> MathResult<u64> {
+    if rank < 1 || rank > winners {
+        return Err(MathError::InvalidRank);
+    }
+    let p = prize as u128;
+    if rank == 1 {
+        let base = (p * RANK1_SHARE_PCT) / PCT_100;
+        let amt = base as u64 + remainder_for_top1; // carry rounding remainder to top1
+        return Ok(amt);
+    }
+    if rank <= 1 + group2 && group2 > 0 {
+        let g2_total = (p * GROUP2_SHARE_PCT) / PCT_100;
+        let each = (g2_total / group2 as u128) as u64;
+        return Ok(each);
+    }
+    if group3 > 0 {
+        let g3_total = (p * GROUP3_SHARE_PCT) / PCT_100;
+        let each = (g3_total / group3 as u128) as u64;
+        return Ok(each);
+    }
+    Ok(0)
+}
+
+// Entry weight for `paid` lamports when one ticket costs `ticket` lamports: paid/ticket in
+// WEIGHT_ONE units, capped at `cap` tickets. Rank-only tiers (cap 0) weigh every entry as one.
+pub fn stake_weight(paid: u64, ticket: u64, cap: u16) -> MathResult<u32> {
+    if cap == 0 {
+        return Ok(WEIGHT_ONE as u32);
+    }
+    if ticket == 0 {
+        return Err(MathError::InvalidAmount);
+    }
+    let weight = (paid as u128 * WEIGHT_ONE as u128 / ticket as u128).min(cap as u128 * WEIGHT_ONE as u128);
+    Ok(weight as u32)
+}
+
+// Per-winner share of the prize pool for `rank` under the classic tiers (50% / 35% / 15%),
+// in parts per SHARE_SCALE. As with the rank-only remainder, an empty group's share goes to rank 1.
+pub fn rank_share_ppt(winners: u32, group2: u32, group3: u32, rank: u32) -> MathResult<u128> {
+    if rank < 1 || rank > winners {
+        return Err(MathError::InvalidRank);
+    }
+    if rank == 1 {
+        let mut pct = RANK1_SHARE_PCT;
+        if group2 == 0 { pct += GROUP2_SHARE_PCT; }
+        if group3 == 0 { pct += GROUP3_SHARE_PCT; }
+        return Ok(SHARE_SCALE * pct / PCT_100);
+    }
+    if rank <= 1 + group2 && group2 > 0 {
+        return Ok(SHARE_SCALE * GROUP2_SHARE_PCT / PCT_100 / group2 as u128);
+    }
+    if group3 > 0 {
+        return Ok(SHARE_SCALE * GROUP3_SHARE_PCT / PCT_100 / group3 as u128);
+    }
+    Ok(0)
+}
+
+pub fn stake_score(share_ppt: u128, weight: u32) -> MathResult<u128> {
+    share_ppt.checked_mul(weight as u128).ok_or(MathError::Overflow)
+}
+
+// prize * score / total_score. With equal weights this reduces to the rank-only split; rounding
+// dust stays in the vault. score <= 5e11 * 1e6, so prize * score fits in u128.
+pub fn stake_weighted_allocation(prize: u64, score: u128, total_score: u128) -> MathResult<u64> {
+    if total_score == 0 || score > total_score {
+        return Err(MathError::ZeroAllocation);
+    }
+    let amount = (prize as u128).checked_mul(score).ok_or(MathError::Overflow)? / total_score;
+    u64::try_from(amount).map_err(|_| MathError::Overflow)
+}
+
+// lamports = ceil(usd_micros * 1e9 / (1e6 * price * 10^expo)). Everything stays in u128 with
+// checked math: usd_micros <= u64::MAX and |expo| <= 18 cannot overflow the numerator.
+pub fn lamports_for_usd_micros_ceil(usd_micros: u64, price: i64, expo: i32) -> MathResult<u64> {
+    if price <= 0 || expo.unsigned_abs() > PYTH_EXPO_ABS_MAX {
+        return Err(MathError::InvalidPrice);
+    }
+    let scale = ten_pow_u128(expo.unsigned_abs())?;
+    let mut numer = (usd_micros as u128)
+        .checked_mul(LAMPORTS_PER_SOL_CONST).ok_or(MathError::Overflow)?;
+    let mut denom = (price as u128)
+        .checked_mul(USD_MICROS).ok_or(MathError::Overflow)?;
+    if expo < 0 {
+        numer = numer.checked_mul(scale).ok_or(MathError::Overflow)?;
+    } else {
+        denom = denom.checked_mul(scale).ok_or(MathError::Overflow)?;
+    }
+
+    let res = ceil_div_u128(numer, denom)?;
+    u64::try_from(res).map_err(|_| MathError::Overflow)
+}
+
+pub fn ten_pow_u128(exp: u32) -> MathResult<u128> {
+    10u128.checked_pow(exp).ok_or(MathError::Overflow)
+}
+
+pub fn ceil_div_u128(numer: u128, denom: u128) -> MathResult<u128> {
+    if denom == 0 {
+        return Err(MathError::Overflow);
+    }
+    Ok(numer.div_ceil(denom))
+}
+
+// Lamports for one member of the guild ranked `rank` (1-based). With fewer than three guilds the
+// missing ranks' percentages go to rank 1.
+pub fn guild_member_share(guild_pool: u64, guild_count: u32, rank: u32, members: u32) -> MathResult<u64> {
+    if rank < 1 || rank as usize > GUILD_RANK_PCT.len() || members == 0 {
+        return Err(MathError::InvalidRank);
+    }
+    let ranked = (guild_count as usize).min(GUILD_RANK_PCT.len());
+    let mut pct = GUILD_RANK_PCT[rank as usize - 1];
+    if rank == 1 {
+        pct += GUILD_RANK_PCT[ranked..].iter().sum::<u64>();
+    }
+    let guild_share = guild_pool as u128 * pct as u128 / PCT_100;
+    Ok((guild_share / members as u128) as u64)
+}
+
+pub fn reward_debt(amount: u64, acc_reward_per_share: u128) -> MathResult<u128> {
+    (amount as u128).checked_mul(acc_reward_per_share).ok_or(MathError::Overflow)
+}
+
+// Share of `net_pool` for a winning bet of `stake` among `winning_total` staked on winners
+// (rounded down; dust stays in the pool account).
+pub fn pari_mutuel_payout(net_pool: u64, stake: u64, winning_total: u64) -> u64 {
+    (net_pool as u128 * stake as u128 / winning_total as u128) as u64
+}
+
+// Referrer's cut of one ticket: `referral_bps` of its 5% revenue bucket (rounded down, so the
+// sum over a match never exceeds the match's revenue bucket).
+pub fn referral_rebate(amount: u64, referral_bps: u16) -> u64 {
+    (amount as u128 * PCT_5 * referral_bps as u128 / (PCT_100 * BPS_100)) as u64
+}
+
+// Streak after an event on `day`, given the day of the previous one. Several events on the same
+// day (e.g. two tiers) count once; events for past days leave it unchanged.
+pub fn next_streak(current: u32, last_day: i64, day: i64) -> u32 {
+    if day == last_day + 1 {
+        current.saturating_add(1)
+    } else if day > last_day + 1 {
+        1
+    } else {
+        current
+    }
+}
+
+// Level reached with `total_xp` (calculateLevel), starting at 1.
+pub fn level_for_xp(total_xp: u64) -> u32 {
+    let mut level = 1u32;
+    let mut accumulated = 0u64;
+    let mut needed = XP_LEVEL_BASE;
+    while let Some(next) = accumulated.checked_add(needed) {
+        if next > total_xp {
+            break;
+        }
+        accumulated = next;
+        level += 1;
+        needed = needed.saturating_mul(6) / 5;
+    }
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // getXPForLevel from src/utils/playerProgress.ts
+    fn xp_for_level(level: u32) -> u64 {
+        let mut xp = XP_LEVEL_BASE;
+        for _ in 2..=level {
+            xp = xp * 6 / 5;
+        }
+        xp
+    }
+
+    // Exact ceil(usd_micros * 1e9 * 10^-expo / (price * 1e6)) for ranges where it fits in u128
+    fn reference_lamports(usd_micros: u64, price: i64, expo: i32) -> u128 {
+        let mut numer = usd_micros as u128 * LAMPORTS_PER_SOL_CONST;
+        let mut denom = price as u128 * USD_MICROS;
+        if expo < 0 {
+            numer *= 10u128.pow(expo.unsigned_abs());
+        } else {
+            denom *= 10u128.pow(expo as u32);
+        }
+        numer.div_ceil(denom)
+    }
+
+    #[test]
+    fn five_dollars_at_150_usd() {
+        // 150.00000000 USD/SOL, expo -8: $5 = 0.0333.. SOL, rounded up
+        let lamports = lamports_for_usd_micros_ceil(5_000_000, 15_000_000_000, -8).unwrap();
+        assert_eq!(lamports, 33_333_334);
+    }
+
+    #[test]
+    fn fractional_ticket_price() {
+        // $2.50 at exactly 100 USD/SOL is 0.025 SOL
+        let lamports = lamports_for_usd_micros_ceil(2_500_000, 10_000_000_000, -8).unwrap();
+        assert_eq!(lamports, 25_000_000);
+    }
+
+    #[test]
+    fn rejects_non_positive_price_and_extreme_expo() {
+        assert!(lamports_for_usd_micros_ceil(5_000_000, 0, -8).is_err());
+        assert!(lamports_for_usd_micros_ceil(5_000_000, -1, -8).is_err());
+        assert!(lamports_for_usd_micros_ceil(5_000_000, 1, -(PYTH_EXPO_ABS_MAX as i32) - 1).is_err());
+        assert!(lamports_for_usd_micros_ceil(5_000_000, 1, PYTH_EXPO_ABS_MAX as i32 + 1).is_err());
+    }
+
+    #[test]
+    fn stake_weight_is_capped() {
+        assert_eq!(stake_weight(3_000, 1_000, 0).unwrap(), WEIGHT_ONE as u32);
+        assert_eq!(stake_weight(1_500, 1_000, 5).unwrap(), 15_000);
+        assert_eq!(stake_weight(50_000, 1_000, 5).unwrap(), 50_000);
+    }
+
+    #[test]
+    fn level_curve_matches_frontend() {
+        // Values of getXPForLevel(1..=8) in playerProgress.ts
+        let expected = [500, 600, 720, 864, 1036, 1243, 1491, 1789];
+        for (i, xp) in expected.iter().enumerate() {
+            assert_eq!(xp_for_level(i as u32 + 1), *xp);
+        }
+        assert_eq!(level_for_xp(0), 1);
+        assert_eq!(level_for_xp(499), 1);
+        assert_eq!(level_for_xp(500), 2);
+        assert_eq!(level_for_xp(1099), 2);
+        assert_eq!(level_for_xp(1100), 3);
+        assert!(level_for_xp(u64::MAX) > 100);
+    }
+
+    #[test]
+    fn guild_bucket_split() {
+        // Three or more guilds: 50/30/20 of the bucket, split per member
+        assert_eq!(guild_member_share(1_000, 5, 1, 1).unwrap(), 500);
+        assert_eq!(guild_member_share(1_000, 5, 2, 3).unwrap(), 100);
+        assert_eq!(guild_member_share(1_000, 5, 3, 4).unwrap(), 50);
+        // Missing ranks go to rank 1
+        assert_eq!(guild_member_share(1_000, 2, 1, 1).unwrap(), 700);
+        assert_eq!(guild_member_share(1_000, 1, 1, 2).unwrap(), 500);
+        assert!(guild_member_share(1_000, 5, 4, 1).is_err());
+        assert!(guild_member_share(1_000, 5, 1, 0).is_err());
+    }
+
+    proptest! {
+        // Winning bets never pay out more than the pool holds after the fee
+        #[test]
+        fn pari_mutuel_payouts_fit_in_pool(
+            winning in proptest::collection::vec(1u64..=1_000_000_000_000, 1..30),
+            losing in 0u64..=1_000_000_000_000_000,
+            fee_bps in 0u16..=PREDICTION_FEE_BPS_MAX,
+        ) {
+            let winning_total: u64 = winning.iter().sum();
+            let total = winning_total + losing;
+            let net = total - (total as u128 * fee_bps as u128 / BPS_100) as u64;
+            let paid: u64 = winning.iter().map(|b| pari_mutuel_payout(net, *b, winning_total)).sum();
+            prop_assert!(paid <= net);
+            prop_assert!(net - paid < winning.len() as u64);
+        }
+
+        // Rebates are paid from the revenue bucket, so they can never exceed it
+        #[test]
+        fn referral_rebates_fit_in_revenue(
+            tickets in proptest::collection::vec(1u64..=100_000_000_000, 1..50),
+            referral_bps in 0u16..=10_000,
+        ) {
+            let pot: u64 = tickets.iter().sum();
+            let rebates: u64 = tickets.iter().map(|t| referral_rebate(*t, referral_bps)).sum();
+            prop_assert!(rebates as u128 <= pot as u128 * PCT_5 / PCT_100);
+        }
+
+        // Equal stakes must pay exactly what the rank-only formula pays, up to rounding
+        #[test]
+        fn equal_weights_match_rank_only(
+            prize in 1u64..=1_000_000_000_000_000_000,
+            players in 1u32..=5_000,
+            multiplier in 1u32..=STAKE_MULTIPLIER_MAX as u32,
+        ) {
+            let (winners, group2, group3) = winner_groups(players);
+            let weight = multiplier * WEIGHT_ONE as u32;
+            let mut total = 0u128;
+            for rank in 1..=winners {
+                total += stake_score(rank_share_ppt(winners, group2, group3, rank).unwrap(), weight).unwrap();
+            }
+            for rank in [1, 1 + group2.min(1), winners] {
+                let score = stake_score(rank_share_ppt(winners, group2, group3, rank).unwrap(), weight).unwrap();
+                let weighted = stake_weighted_allocation(prize, score, total).unwrap();
+                let remainder = compute_remainder_for_top1(prize, winners, group2, group3);
+                let classic = compute_rank_allocation(prize, winners, group2, group3, rank, remainder).unwrap();
+                // share truncation is < 1 ppt per winner; rank-only payouts also hand rank 1
+                // the rounding dust of every group
+                let dust = if rank == 1 { remainder } else { 0 };
+                let tolerance = 1 + dust + (prize as u128 * winners as u128 / SHARE_SCALE) as u64;
+                prop_assert!(weighted.abs_diff(classic) <= tolerance);
+            }
+        }
+
+        // Any price, any supported exponent, any configurable ticket: never panics and
+        // only fails when the result does not fit in u64.
+        #[test]
+        fn never_panics(
+            usd_micros in 1..=TICKET_USD_MICROS_MAX,
+            price in 1..=i64::MAX,
+            expo in -(PYTH_EXPO_ABS_MAX as i32)..=PYTH_EXPO_ABS_MAX as i32,
+        ) {
+            let _ = lamports_for_usd_micros_ceil(usd_micros, price, expo);
+        }
+
+        #[test]
+        fn matches_exact_ceiling(
+            usd_micros in 1..=TICKET_USD_MICROS_MAX,
+            price in 1..=1_000_000_000_000i64,
+            expo in -12i32..=4,
+        ) {
+            let expected = reference_lamports(usd_micros, price, expo);
+            match lamports_for_usd_micros_ceil(usd_micros, price, expo) {
+                Ok(lamports) => prop_assert_eq!(lamports as u128, expected),
+                Err(_) => prop_assert!(expected > u64::MAX as u128),
+            }
+        }
+
+        #[test]
+        fn monotonic_in_ticket_price(
+            a in 1..=TICKET_USD_MICROS_MAX,
+            b in 1..=TICKET_USD_MICROS_MAX,
+            price in 1_000_000i64..=1_000_000_000_000,
+        ) {
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            let lo = lamports_for_usd_micros_ceil(lo, price, -8).unwrap();
+            let hi = lamports_for_usd_micros_ceil(hi, price, -8).unwrap();
+            prop_assert!(lo <= hi);
+        }
+    }
+}
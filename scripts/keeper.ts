@@ -43,7 +43,7 @@ async function main() {
 
   const yesterday = getYesterdayUtcDayId()
 
-  // 1) finalize_match(yesterday)
+  // 1) finalize_match(yesterday): fails with TooEarlyToFinalize until config.join_grace_secs past midnight
   try {
     // Note: accounts include config, stats, match_state, match_vault, buyback vaults, revenue wallet
     // You must pass the correct PDAs here (omitted for brevity). Use the same derivations as in src/chain/arena.ts
@@ -60,7 +60,10 @@ async function main() {
   // const url = `https://mainnet.helius-rpc.com/?api-key=${HELIUS_API_KEY}`
   // post JSON-RPC methods like getSignaturesForAddress or getTransactions for UranusPerps events
 
-  // 3) For each winner by rank, call record_allocation(rank) with their pubkey
+  // 3) For each winner in rank order (1, 2, ...), call record_allocation(rank) with their pubkey;
+  //    each must confirm before the next rank is accepted. This holds for rank-only and
+  //    stake-weighted matches alike: a rank other than the next one fails with InvalidRank, so
+  //    never send ranks in parallel, and after a failure resume from allocations_recorded + 1.
   // await program.methods.recordAllocation(rank).accounts({...winner pk..., match pk...}).rpc()
 
   console.log('Keeper finished')
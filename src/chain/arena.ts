@@ -11,6 +11,14 @@ const SEED_ALLOC = enc.encode('alloc')
 const SEED_CONFIG = enc.encode('config')
 const SEED_STATS = enc.encode('stats')
 const SEED_ENTRY = enc.encode('entry')
+const SEED_TIER = enc.encode('tier')
+const SEED_PROFILE = enc.encode('profile')
+const SEED_REFERRAL = enc.encode('referral')
+const SEED_BET_MARK = enc.encode('bet_mark')
+
+// Arena tiers: 0 = $5 daily arena, 1 = $50 high stakes
+export const TIER_DAILY = 0
+export const TIER_HIGH_STAKES = 1
 
 function dayToLeBytes(dayId: number): Uint8Array {
   const buf = new ArrayBuffer(8)
@@ -38,8 +46,11 @@ export function deriveConfigPda() {
 export function deriveStatsPda(configPk: PublicKey) {
   return PublicKey.findProgramAddressSync([SEED_STATS, configPk.toBytes()], PROGRAM_ID)[0]
 }
-export function deriveMatchPda(dayId: number) {
-  return PublicKey.findProgramAddressSync([SEED_MATCH, dayToLeBytes(dayId)], PROGRAM_ID)[0]
+export function deriveTierPda(tierId: number = TIER_DAILY) {
+  return PublicKey.findProgramAddressSync([SEED_TIER, Uint8Array.of(tierId)], PROGRAM_ID)[0]
+}
+export function deriveMatchPda(dayId: number, tierId: number = TIER_DAILY) {
+  return PublicKey.findProgramAddressSync([SEED_MATCH, deriveTierPda(tierId).toBytes(), dayToLeBytes(dayId)], PROGRAM_ID)[0]
 }
 export function deriveVaultPda(matchPk: PublicKey) {
   return PublicKey.findProgramAddressSync([SEED_VAULT, matchPk.toBytes()], PROGRAM_ID)[0]
@@ -47,6 +58,35 @@ export function deriveVaultPda(matchPk: PublicKey) {
 export function deriveAllocationPda(matchPk: PublicKey, player: PublicKey) {
   return PublicKey.findProgramAddressSync([SEED_ALLOC, matchPk.toBytes(), player.toBytes()], PROGRAM_ID)[0]
 }
+export function deriveProfilePda(player: PublicKey) {
+  return PublicKey.findProgramAddressSync([SEED_PROFILE, player.toBytes()], PROGRAM_ID)[0]
+}
+// Referred players pass their referrer's ReferralAccount on every join (binds on the first match)
+export function deriveReferralPda(referrer: PublicKey) {
+  return PublicKey.findProgramAddressSync([SEED_REFERRAL, referrer.toBytes()], PROGRAM_ID)[0]
+}
+export function deriveBetMarkPda(matchPk: PublicKey, wallet: PublicKey) {
+  return PublicKey.findProgramAddressSync([SEED_BET_MARK, matchPk.toBytes(), wallet.toBytes()], PROGRAM_ID)[0]
+}
+
+// PlayerProfile.referrer: 8-byte discriminator plus the fields before it (see state/profile.rs)
+const PROFILE_REFERRER_OFFSET = 113
+
+// Referrer bound in the wallet's PlayerProfile, or null if none (or no profile yet)
+export async function fetchBoundReferrer(connection: Connection, player: PublicKey): Promise<PublicKey | null> {
+  const info = await connection.getAccountInfo(deriveProfilePda(player))
+  if (!info || info.data.length < PROFILE_REFERRER_OFFSET + 32) return null
+  const referrer = new PublicKey(info.data.subarray(PROFILE_REFERRER_OFFSET, PROFILE_REFERRER_OFFSET + 32))
+  return referrer.equals(PublicKey.default) ? null : referrer
+}
+
+// Once bound, the program requires the bound referrer's account on every join (MissingReferral
+// otherwise); before that, a referral link's referrer is passed so the first match binds it.
+async function resolveReferral(connection: Connection, player: PublicKey, referrer: PublicKey | null): Promise<PublicKey | null> {
+  const bound = await fetchBoundReferrer(connection, player)
+  const r = bound ?? referrer
+  return r ? deriveReferralPda(r) : null
+}
 
 export async function buildClaimIx(connection: Connection, walletPubkey: PublicKey, dayId: number): Promise<TransactionInstruction> {
   const matchState = deriveMatchPda(dayId)
@@ -59,32 +99,90 @@ export async function buildClaimIx(connection: Connection, walletPubkey: PublicK
     .claim()
     .accounts({
       winner: walletPubkey,
+      config: deriveConfigPda(),
       matchState,
       matchVault,
       allocation,
+      profile: deriveProfilePda(walletPubkey),
       systemProgram: SystemProgram.programId,
     })
     .instruction()
   return ix
 }
 
-export async function buildJoinIx(connection: Connection, walletPubkey: PublicKey, lamports: number, pythPriceAccount: PublicKey, dayId: number): Promise<TransactionInstruction> {
+export async function buildJoinIx(connection: Connection, walletPubkey: PublicKey, lamports: number, priceUpdate: PublicKey, dayId: number, referrer: PublicKey | null = null): Promise<TransactionInstruction> {
   const config = deriveConfigPda()
-  const matchState = deriveMatchPda(dayId)
+  const tier = deriveTierPda(TIER_DAILY)
+  const matchState = deriveMatchPda(dayId, TIER_DAILY)
   const matchVault = deriveVaultPda(matchState)
   const entry = PublicKey.findProgramAddressSync([SEED_ENTRY, matchState.toBytes(), walletPubkey.toBytes()], PROGRAM_ID)[0]
+  const referral = await resolveReferral(connection, walletPubkey, referrer)
 
   const provider = new AnchorProvider(connection as any, {} as any, {})
   const program = new Program(idl as Idl, PROGRAM_ID, provider)
   const ix = await program.methods
-    .join(new BN(lamports))
+    .join(new BN(dayId), new BN(lamports))
     .accounts({
       player: walletPubkey,
       config,
+      tier,
       matchState,
       matchVault,
       entry,
-      pythPriceAccount,
+      profile: deriveProfilePda(walletPubkey),
+      referral,
+      // Solo tiers; team arenas also pass the member and guild standing PDAs
+      guildMember: null,
+      guildStanding: null,
+      // Open tiers; holder-only tiers also pass the player's gate token account (and NFT metadata and mint)
+      gateTokens: null,
+      gateMetadata: null,
+      gateNftMint: null,
+      // Public tiers; tournaments also pass the player's invite PDA
+      invite: null,
+      // Must not exist: wallets that bet on the match cannot join it
+      betMark: deriveBetMarkPda(matchState, walletPubkey),
+      priceUpdate,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction()
+  return ix
+}
+
+// Pays exactly one ticket at the on-chain oracle price; fails if that exceeds maxLamports
+export async function buildJoinExactIx(connection: Connection, walletPubkey: PublicKey, maxLamports: number, priceUpdate: PublicKey, dayId: number, referrer: PublicKey | null = null): Promise<TransactionInstruction> {
+  const config = deriveConfigPda()
+  const tier = deriveTierPda(TIER_DAILY)
+  const matchState = deriveMatchPda(dayId, TIER_DAILY)
+  const matchVault = deriveVaultPda(matchState)
+  const entry = PublicKey.findProgramAddressSync([SEED_ENTRY, matchState.toBytes(), walletPubkey.toBytes()], PROGRAM_ID)[0]
+  const referral = await resolveReferral(connection, walletPubkey, referrer)
+
+  const provider = new AnchorProvider(connection as any, {} as any, {})
+  const program = new Program(idl as Idl, PROGRAM_ID, provider)
+  const ix = await program.methods
+    .joinExact(new BN(dayId), new BN(maxLamports))
+    .accounts({
+      player: walletPubkey,
+      config,
+      tier,
+      matchState,
+      matchVault,
+      entry,
+      profile: deriveProfilePda(walletPubkey),
+      referral,
+      // Solo tiers; team arenas also pass the member and guild standing PDAs
+      guildMember: null,
+      guildStanding: null,
+      // Open tiers; holder-only tiers also pass the player's gate token account (and NFT metadata and mint)
+      gateTokens: null,
+      gateMetadata: null,
+      gateNftMint: null,
+      // Public tiers; tournaments also pass the player's invite PDA
+      invite: null,
+      // Must not exist: wallets that bet on the match cannot join it
+      betMark: deriveBetMarkPda(matchState, walletPubkey),
+      priceUpdate,
       systemProgram: SystemProgram.programId,
     })
     .instruction()
@@ -152,9 +250,13 @@ export function parseAllocation(data: Uint8Array): AllocationLite | null {
   return { amount, claimed }
 }
 
+// base58 of sha256("account:MatchState")[0..8]
+const MATCH_STATE_DISCRIMINATOR_B58 = 'ixFiGarkXzF'
+
 export async function fetchAllMatches(connection: Connection): Promise<MatchStateLite[]> {
   const accounts = await connection.getProgramAccounts(PROGRAM_ID, {
-    filters: [{ dataSize: 8 + 54 }],
+    // MatchState grows with new features, so match on the account discriminator instead of its size
+    filters: [{ memcmp: { offset: 0, bytes: MATCH_STATE_DISCRIMINATOR_B58 } }],
   })
   return accounts
     .map(a => parseMatchState(a.account.data)!)